@@ -0,0 +1,79 @@
+// safe wrappers of the host functions of hello_extism_shared::host_fns. the
+// names in the extern block are the ones of the constants there.
+
+use extism_pdk::*;
+use hello_extism_shared::host_fns::*;
+use std::collections::BTreeMap;
+
+#[host_fn]
+extern "ExtismHost" {
+    fn hello_log(record: LogRecord);
+    fn hello_kv_get(key: KvKey) -> KvValue;
+    fn hello_kv_set(set: KvSet);
+    fn hello_clock(args: ClockArgs) -> ClockNow;
+    fn hello_config_get(key: ConfigKey) -> ConfigValue;
+}
+
+// log the message with fields like [("key", "visits")]
+pub fn log(level: LogLevel, message: &str, fields: &[(&str, &str)]) -> Result<(), Error> {
+    let fields: BTreeMap<String, String> = fields
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    let record = LogRecord {
+        level,
+        message: message.to_string(),
+        fields,
+    };
+    unsafe { hello_log(record) }
+}
+
+pub fn kv_get(namespace: &str, key: &str) -> Result<Option<Vec<u8>>, Error> {
+    let key = KvKey {
+        namespace: namespace.to_string(),
+        key: key.to_string(),
+    };
+    let out = unsafe { hello_kv_get(key)? };
+    Ok(out.value)
+}
+
+pub fn kv_set(namespace: &str, key: &str, value: &[u8]) -> Result<(), Error> {
+    let set = KvSet {
+        namespace: namespace.to_string(),
+        key: key.to_string(),
+        value: Some(value.to_vec()),
+    };
+    unsafe { hello_kv_set(set) }
+}
+
+pub fn kv_delete(namespace: &str, key: &str) -> Result<(), Error> {
+    let set = KvSet {
+        namespace: namespace.to_string(),
+        key: key.to_string(),
+        value: None,
+    };
+    unsafe { hello_kv_set(set) }
+}
+
+// milliseconds since the unix epoch
+pub fn now_millis() -> Result<u64, Error> {
+    clock(ClockKind::Wall)
+}
+
+// milliseconds since the plugin was created
+pub fn monotonic_millis() -> Result<u64, Error> {
+    clock(ClockKind::Monotonic)
+}
+
+fn clock(clock: ClockKind) -> Result<u64, Error> {
+    let out = unsafe { hello_clock(ClockArgs { clock })? };
+    Ok(out.millis)
+}
+
+pub fn config_get(key: &str) -> Result<Option<String>, Error> {
+    let key = ConfigKey {
+        key: key.to_string(),
+    };
+    let out = unsafe { hello_config_get(key)? };
+    Ok(out.value)
+}
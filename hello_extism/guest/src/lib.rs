@@ -1,9 +1,56 @@
+mod host;
+
 use extism_pdk::*;
+use hello_extism_shared::host_fns::*;
 use hello_extism_shared::*;
 
+const NAMESPACE: &str = "hello";
+
+// the greeting is "greeting" of the host config, "Hello" by default
 #[plugin_fn]
 pub fn greet(name: String) -> FnResult<String> {
-    Ok(format!("Hello {}!", name))
+    let greeting = host::config_get("greeting")?.unwrap_or("Hello".to_string());
+    host::log(LogLevel::Info, "greet", &[("name", &name)])?;
+    Ok(format!("{} {}!", greeting, name))
+}
+
+// count the calls with the key in the key-value store of the host
+#[plugin_fn]
+pub fn count(key: String) -> FnResult<String> {
+    let count = get_count(&key)? + 1;
+    host::kv_set(NAMESPACE, &key, count.to_string().as_bytes())?;
+    host::log(
+        LogLevel::Debug,
+        "count",
+        &[("key", &key), ("count", &count.to_string())],
+    )?;
+    Ok(count.to_string())
+}
+
+// delete the count of the key, returns the count before
+#[plugin_fn]
+pub fn reset(key: String) -> FnResult<String> {
+    let count = get_count(&key)?;
+    host::kv_delete(NAMESPACE, &key)?;
+    Ok(count.to_string())
+}
+
+fn get_count(key: &str) -> Result<u64, Error> {
+    let count = match host::kv_get(NAMESPACE, key)? {
+        Some(bytes) => String::from_utf8(bytes)?.parse::<u64>()?,
+        None => 0,
+    };
+    Ok(count)
+}
+
+// the wall clock of the host, and how long the plugin lives
+#[plugin_fn]
+pub fn clock() -> FnResult<ListString> {
+    let items = vec![
+        host::now_millis()?.to_string(),
+        host::monotonic_millis()?.to_string(),
+    ];
+    Ok(ListString { items })
 }
 
 #[plugin_fn]
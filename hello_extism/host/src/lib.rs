@@ -0,0 +1,103 @@
+// the host side of the host functions of hello_extism_shared::host_fns
+
+use extism::*;
+use hello_extism_shared::host_fns::*;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// what the host functions of one plugin read and write
+pub struct HostState {
+    // the records of hello_log, also printed to stderr
+    pub logs: Vec<LogRecord>,
+    // namespace -> key -> value
+    pub kv: BTreeMap<String, BTreeMap<String, Vec<u8>>>,
+    pub config: BTreeMap<String, String>,
+    // a fixed wall clock for the tests, the system clock when None
+    pub wall_millis: Option<u64>,
+    pub started: Instant,
+}
+
+impl HostState {
+    pub fn new(config: BTreeMap<String, String>) -> Self {
+        Self {
+            logs: vec![],
+            kv: BTreeMap::new(),
+            config,
+            wall_millis: None,
+            started: Instant::now(),
+        }
+    }
+}
+
+host_fn!(hello_log(state: HostState; record: LogRecord) {
+    let state = state.get()?;
+    let mut state = state.lock().unwrap();
+    let fields: Vec<String> = record
+        .fields
+        .iter()
+        .map(|(name, value)| format!(" {}={}", name, value))
+        .collect();
+    eprintln!("[guest] {:?} {}{}", record.level, record.message, fields.concat());
+    state.logs.push(record);
+    Ok(())
+});
+
+host_fn!(hello_kv_get(state: HostState; key: KvKey) -> KvValue {
+    let state = state.get()?;
+    let state = state.lock().unwrap();
+    let value = state
+        .kv
+        .get(&key.namespace)
+        .and_then(|values| values.get(&key.key))
+        .cloned();
+    Ok(KvValue { value })
+});
+
+host_fn!(hello_kv_set(state: HostState; set: KvSet) {
+    let state = state.get()?;
+    let mut state = state.lock().unwrap();
+    let values = state.kv.entry(set.namespace).or_default();
+    match set.value {
+        Some(value) => {
+            values.insert(set.key, value);
+        }
+        None => {
+            values.remove(&set.key);
+        }
+    }
+    Ok(())
+});
+
+host_fn!(hello_clock(state: HostState; args: ClockArgs) -> ClockNow {
+    let state = state.get()?;
+    let state = state.lock().unwrap();
+    let millis = match args.clock {
+        ClockKind::Wall => match state.wall_millis {
+            Some(millis) => millis,
+            None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64,
+        },
+        ClockKind::Monotonic => state.started.elapsed().as_millis() as u64,
+    };
+    Ok(ClockNow { millis })
+});
+
+host_fn!(hello_config_get(state: HostState; key: ConfigKey) -> ConfigValue {
+    let state = state.get()?;
+    let state = state.lock().unwrap();
+    let value = state.config.get(&key.key).cloned();
+    Ok(ConfigValue { value })
+});
+
+// the guest with the host functions working on the state
+pub fn load_guest(guest_file: &Path, state: &UserData<HostState>) -> Result<Plugin, Error> {
+    let manifest = Manifest::new([Wasm::file(guest_file)]);
+    PluginBuilder::new(manifest)
+        .with_wasi(true)
+        .with_function(LOG, [PTR], [], state.clone(), hello_log)
+        .with_function(KV_GET, [PTR], [PTR], state.clone(), hello_kv_get)
+        .with_function(KV_SET, [PTR], [], state.clone(), hello_kv_set)
+        .with_function(CLOCK, [PTR], [PTR], state.clone(), hello_clock)
+        .with_function(CONFIG_GET, [PTR], [PTR], state.clone(), hello_config_get)
+        .build()
+}
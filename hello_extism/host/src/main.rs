@@ -1,5 +1,7 @@
 use extism::*;
+use hello_extism_host::*;
 use hello_extism_shared::*;
+use std::collections::BTreeMap;
 use std::{env, path::PathBuf};
 
 fn find_root_dir() -> Option<PathBuf> {
@@ -30,9 +32,21 @@ Hint: cd hello_extism/guest
         );
     }
 
-    let manifest = Manifest::new([Wasm::file(guest_file)]);
+    let mut config = BTreeMap::new();
+    config.insert("greeting".to_string(), "Hi".to_string());
+    let state = UserData::new(HostState::new(config));
+    let mut guest = load_guest(&guest_file, &state).unwrap();
 
-    let mut guest = Plugin::new(manifest, [], true).unwrap();
+    {
+        let res = guest.call::<&str, &str>("greet", "extism").unwrap();
+        println!("greet: {}", res);
+        for _ in 0..2 {
+            let res = guest.call::<&str, &str>("count", "visits").unwrap();
+            println!("count: {}", res);
+        }
+        let res = guest.call::<&str, ListString>("clock", "").unwrap();
+        println!("clock: {:?}", res.items);
+    }
 
     {
         let args = AddArgs {
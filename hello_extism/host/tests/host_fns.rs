@@ -0,0 +1,104 @@
+// run the compiled guest against the host functions. the guest is built for
+// wasm32-unknown-unknown into a target dir of its own, so the build does not
+// wait for the lock of the running cargo test.
+
+use extism::*;
+use hello_extism_host::*;
+use hello_extism_shared::host_fns::*;
+use hello_extism_shared::*;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+fn guest_file() -> &'static PathBuf {
+    static GUEST: OnceLock<PathBuf> = OnceLock::new();
+    GUEST.get_or_init(|| {
+        let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("guest");
+        let status = Command::new(env!("CARGO"))
+            .args(["build", "-p", "hello_extism_guest"])
+            .args(["--target", "wasm32-unknown-unknown"])
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .status()
+            .unwrap();
+        assert!(
+            status.success(),
+            "failed to build the guest, is the target installed?\n\
+             rustup target add wasm32-unknown-unknown"
+        );
+        target_dir.join("wasm32-unknown-unknown/debug/hello_extism_guest.wasm")
+    })
+}
+
+fn load(config: &[(&str, &str)]) -> (Plugin, UserData<HostState>) {
+    let config = config
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    let state = UserData::new(HostState::new(config));
+    let guest = load_guest(guest_file(), &state).unwrap();
+    (guest, state)
+}
+
+#[test]
+fn test_config_and_log() {
+    let (mut guest, state) = load(&[("greeting", "Hi")]);
+    let res = guest.call::<&str, &str>("greet", "alice").unwrap();
+    assert_eq!(res, "Hi alice!");
+
+    let state = state.get().unwrap();
+    let state = state.lock().unwrap();
+    let mut fields = BTreeMap::new();
+    fields.insert("name".to_string(), "alice".to_string());
+    assert_eq!(
+        state.logs,
+        vec![LogRecord {
+            level: LogLevel::Info,
+            message: "greet".to_string(),
+            fields,
+        }]
+    );
+
+    let (mut guest, _) = load(&[]);
+    let res = guest.call::<&str, &str>("greet", "bob").unwrap();
+    assert_eq!(res, "Hello bob!");
+}
+
+#[test]
+fn test_kv() {
+    let (mut guest, state) = load(&[]);
+    let mut count = |key: &str| guest.call::<&str, String>("count", key).unwrap();
+    assert_eq!(count("visits"), "1");
+    assert_eq!(count("visits"), "2");
+    assert_eq!(count("other"), "1");
+    {
+        let state = state.get().unwrap();
+        let state = state.lock().unwrap();
+        let values = &state.kv["hello"];
+        assert_eq!(values["visits"], b"2".to_vec());
+        assert_eq!(state.kv.len(), 1);
+        assert_eq!(state.logs.len(), 3);
+    }
+
+    let res = guest.call::<&str, &str>("reset", "visits").unwrap();
+    assert_eq!(res, "2");
+    let state = state.get().unwrap();
+    let state = state.lock().unwrap();
+    assert!(!state.kv["hello"].contains_key("visits"));
+    assert!(state.kv["hello"].contains_key("other"));
+}
+
+#[test]
+fn test_clock() {
+    let (mut guest, state) = load(&[]);
+    state.get().unwrap().lock().unwrap().wall_millis = Some(1_700_000_000_000);
+    let res = guest.call::<&str, ListString>("clock", "").unwrap();
+    assert_eq!(res.items[0], "1700000000000");
+    let first: u64 = res.items[1].parse().unwrap();
+
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    let res = guest.call::<&str, ListString>("clock", "").unwrap();
+    let second: u64 = res.items[1].parse().unwrap();
+    assert!(second >= first + 20);
+}
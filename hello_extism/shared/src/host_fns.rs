@@ -0,0 +1,153 @@
+// the host functions the host gives to the guest, with the types of their
+// argument and result. the guest imports them by these names from the
+// "extism:host/user" module, see guest/src/host.rs
+
+use crate::wire::*;
+use serde::*;
+use std::collections::BTreeMap;
+
+pub const LOG: &str = "hello_log";
+pub const KV_GET: &str = "hello_kv_get";
+pub const KV_SET: &str = "hello_kv_set";
+pub const CLOCK: &str = "hello_clock";
+pub const CONFIG_GET: &str = "hello_config_get";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+// `hello_log`: a message with key-value fields, e.g. `count key=visits`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub message: String,
+    pub fields: BTreeMap<String, String>,
+}
+
+// `hello_kv_get`: the key of a value, keys of different namespaces do not
+// collide
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KvKey {
+    pub namespace: String,
+    pub key: String,
+}
+
+// the result of `hello_kv_get`, `None` for a missing key
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KvValue {
+    pub value: Option<Vec<u8>>,
+}
+
+// `hello_kv_set`: `None` deletes the key
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KvSet {
+    pub namespace: String,
+    pub key: String,
+    pub value: Option<Vec<u8>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ClockKind {
+    // milliseconds since the unix epoch
+    Wall,
+    // milliseconds since the plugin was created, never goes back
+    Monotonic,
+}
+
+// `hello_clock`: which clock to read
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClockArgs {
+    pub clock: ClockKind,
+}
+
+// the result of `hello_clock`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClockNow {
+    pub millis: u64,
+}
+
+// `hello_config_get`: the name of a config value of the host
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConfigKey {
+    pub key: String,
+}
+
+// the result of `hello_config_get`, `None` when the host has no such value
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConfigValue {
+    pub value: Option<String>,
+}
+
+impl Versioned for LogRecord {
+    const VERSION: u16 = 1;
+}
+
+impl Versioned for KvKey {
+    const VERSION: u16 = 1;
+}
+
+impl Versioned for KvValue {
+    const VERSION: u16 = 1;
+}
+
+impl Versioned for KvSet {
+    const VERSION: u16 = 1;
+}
+
+impl Versioned for ClockArgs {
+    const VERSION: u16 = 1;
+}
+
+impl Versioned for ClockNow {
+    const VERSION: u16 = 1;
+}
+
+impl Versioned for ConfigKey {
+    const VERSION: u16 = 1;
+}
+
+impl Versioned for ConfigValue {
+    const VERSION: u16 = 1;
+}
+
+wire_bytes!(
+    msgpack: LogRecord,
+    KvKey,
+    KvValue,
+    KvSet,
+    ClockArgs,
+    ClockNow,
+    ConfigKey,
+    ConfigValue
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use extism_convert::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut fields = BTreeMap::new();
+        fields.insert("key".to_string(), "visits".to_string());
+        let record = LogRecord {
+            level: LogLevel::Info,
+            message: "count".to_string(),
+            fields,
+        };
+        let bytes = record.to_bytes().unwrap();
+        assert_eq!(LogRecord::from_bytes_owned(&bytes).unwrap(), record);
+
+        let set = KvSet {
+            namespace: "hello".to_string(),
+            key: "visits".to_string(),
+            value: None,
+        };
+        let bytes = set.to_bytes().unwrap();
+        assert_eq!(KvSet::from_bytes_owned(&bytes).unwrap(), set);
+    }
+}
@@ -1,14 +1,17 @@
-use extism_convert::*;
+#[macro_use]
+pub mod wire;
+pub mod host_fns;
+
 use serde::*;
+use wire::*;
 
-#[derive(Serialize, Deserialize, ToBytes, FromBytes)]
-#[encoding(Msgpack)]
+// see wire.rs for how fields may change
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct AddArgs {
     pub args: Vec<i64>,
 }
 
-#[derive(Serialize, Deserialize, ToBytes, FromBytes)]
-#[encoding(Msgpack)]
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct AddOut {
     pub result: i64,
     pub overflow: bool,
@@ -19,33 +22,66 @@ pub struct ListString {
     pub items: Vec<String>,
 }
 
-impl FromBytesOwned for ListString {
-    fn from_bytes_owned(data: &[u8]) -> Result<Self, Error> {
-        let out: Self = bincode::deserialize(data)?;
-        Ok(out)
-    }
+impl Versioned for AddArgs {
+    const VERSION: u16 = 1;
 }
 
-impl<'a> ToBytes<'a> for ListString {
-    type Bytes = Vec<u8>;
+impl Versioned for AddOut {
+    const VERSION: u16 = 1;
+}
 
-    fn to_bytes(&self) -> Result<Self::Bytes, Error> {
-        let out = bincode::serialize(self)?;
-        Ok(out)
-    }
+impl Versioned for ListString {
+    const VERSION: u16 = 1;
 }
 
+wire_bytes!(msgpack: AddArgs, AddOut);
+wire_bytes!(bincode: ListString);
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use extism_convert::*;
 
     #[derive(Serialize, Deserialize, Debug, PartialEq)]
     struct ListStringV2 {
         pub items: Vec<String>,
+        #[serde(default)]
         pub new_str: String,
+        #[serde(default)]
         pub new_int: i32,
     }
 
+    impl Versioned for ListStringV2 {
+        const VERSION: u16 = 2;
+
+        fn upgrade(version: u16, payload: &[u8]) -> Result<Self, Error> {
+            match version {
+                1 => {
+                    let v1: ListString = bincode::deserialize(payload)?;
+                    Ok(ListStringV2 {
+                        items: v1.items,
+                        new_str: String::new(),
+                        new_int: 0,
+                    })
+                }
+                _ => Err(Error::msg(format!("unknown version {}", version))),
+            }
+        }
+    }
+
+    // AddOut as a newer guest may send it
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct AddOutV2 {
+        pub result: i64,
+        pub overflow: bool,
+        #[serde(default)]
+        pub count: usize,
+    }
+
+    impl Versioned for AddOutV2 {
+        const VERSION: u16 = 2;
+    }
+
     #[test]
     fn test_bincode() {
         let input = ListString {
@@ -56,13 +92,53 @@ mod test {
         assert_eq!(input, output);
     }
 
-    #[test] // ERROR
+    // plain bincode has no room for added fields, see wire.rs
+    #[test]
     fn test_bincode_v2() {
         let input = ListString {
             items: vec!["hello".to_string(), "world".to_string()],
         };
         let bytes = bincode::serialize(&input).unwrap();
-        let output: ListStringV2 = bincode::deserialize(&bytes).unwrap();
+        assert!(bincode::deserialize::<ListStringV2>(&bytes).is_err());
+
+        let bytes = input.to_bytes().unwrap();
+        let output: ListStringV2 = from_bincode(&bytes).unwrap();
         assert_eq!(output.items, input.items);
+        assert_eq!(output.new_int, 0);
+
+        // and back, the older reader ignores the new fields
+        let output = ListStringV2 {
+            items: vec!["a".to_string()],
+            new_str: "b".to_string(),
+            new_int: 1,
+        };
+        let bytes = to_bincode(&output).unwrap();
+        let input = ListString::from_bytes_owned(&bytes).unwrap();
+        assert_eq!(input.items, output.items);
+    }
+
+    #[test]
+    fn test_add_msgpack() {
+        let args = AddArgs { args: vec![1, 2] };
+        let bytes = args.to_bytes().unwrap();
+        assert_eq!(AddArgs::from_bytes_owned(&bytes).unwrap(), args);
+
+        let out = AddOutV2 {
+            result: 3,
+            overflow: false,
+            count: 2,
+        };
+        let bytes = to_msgpack(&out).unwrap();
+        let old = AddOut::from_bytes_owned(&bytes).unwrap();
+        assert_eq!(
+            old,
+            AddOut {
+                result: 3,
+                overflow: false
+            }
+        );
+        let bytes = old.to_bytes().unwrap();
+        let new: AddOutV2 = from_msgpack(&bytes).unwrap();
+        assert_eq!(new.count, 0);
     }
 }
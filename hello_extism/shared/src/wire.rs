@@ -0,0 +1,324 @@
+// the envelope of the types which cross the host/guest boundary, so that a
+// newer host can call an older guest and the other way around.
+//
+// the compatibility policy:
+// - each type has a VERSION, bumped with every change of its fields
+// - fields are only added at the end, never reordered or retyped
+// - a field added after the first version has #[serde(default)], so payloads
+//   without it decode to the default
+// - msgpack writes fields by name, so a reader skips the fields it does not
+//   know and defaults the ones it misses. a field can be removed when the
+//   readers which still know it default it
+// - bincode writes fields by position. a reader ignores the fields after the
+//   ones it knows, and an older payload is decoded with the layout of its
+//   version by Versioned::upgrade. a field is never removed from a bincode
+//   layout, it is kept and written as its default
+// - a change which can not follow these rules is a new type and a new plugin
+//   function
+
+pub use extism_convert;
+
+use extism_convert::Error;
+use serde::de::DeserializeOwned;
+use serde::*;
+
+pub trait Versioned: Serialize + DeserializeOwned {
+    const VERSION: u16;
+
+    // the value from a bincode payload of an older version. the default is
+    // for the first version, which has no older ones.
+    fn upgrade(version: u16, _payload: &[u8]) -> Result<Self, Error> {
+        Err(Error::msg(format!(
+            "unknown version {} of {}",
+            version,
+            std::any::type_name::<Self>()
+        )))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u16,
+    body: T,
+}
+
+// {"version": 1, "body": {"field": ...}} with the fields by name
+pub fn to_msgpack<T: Versioned>(value: &T) -> Result<Vec<u8>, Error> {
+    let envelope = Envelope {
+        version: T::VERSION,
+        body: value,
+    };
+    Ok(rmp_serde::to_vec_named(&envelope)?)
+}
+
+pub fn from_msgpack<T: Versioned>(data: &[u8]) -> Result<T, Error> {
+    let envelope: Envelope<T> = rmp_serde::from_slice(data)?;
+    Ok(envelope.body)
+}
+
+// the version as u16, then the fields in order
+pub fn to_bincode<T: Versioned>(value: &T) -> Result<Vec<u8>, Error> {
+    Ok(bincode::serialize(&(T::VERSION, value))?)
+}
+
+pub fn from_bincode<T: Versioned>(data: &[u8]) -> Result<T, Error> {
+    if data.len() < 2 {
+        return Err(Error::msg("missing the version of the payload"));
+    }
+    let version: u16 = bincode::deserialize(&data[..2])?;
+    let payload = &data[2..];
+    if version < T::VERSION {
+        return T::upgrade(version, payload);
+    }
+    // the fields of a newer version follow the ones of this version
+    Ok(bincode::deserialize(payload)?)
+}
+
+// ToBytes and FromBytesOwned with the envelope, e.g. wire_bytes!(msgpack: AddArgs)
+#[macro_export]
+macro_rules! wire_bytes {
+    (msgpack: $($ty:ty),+) => {
+        $crate::wire_bytes!(@impl $crate::wire::to_msgpack, $crate::wire::from_msgpack, $($ty),+);
+    };
+    (bincode: $($ty:ty),+) => {
+        $crate::wire_bytes!(@impl $crate::wire::to_bincode, $crate::wire::from_bincode, $($ty),+);
+    };
+    (@impl $to:path, $from:path, $($ty:ty),+) => {
+        $(
+            impl $crate::wire::extism_convert::FromBytesOwned for $ty {
+                fn from_bytes_owned(data: &[u8]) -> Result<Self, $crate::wire::extism_convert::Error> {
+                    $from(data)
+                }
+            }
+
+            impl<'a> $crate::wire::extism_convert::ToBytes<'a> for $ty {
+                type Bytes = Vec<u8>;
+
+                fn to_bytes(&self) -> Result<Self::Bytes, $crate::wire::extism_convert::Error> {
+                    $to(self)
+                }
+            }
+        )+
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // a type in three versions: v2 adds "done", v3 adds "note" and in
+    // msgpack drops "count", which v2 defaults
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct ItemV1 {
+        title: String,
+        count: u32,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct ItemV2 {
+        title: String,
+        #[serde(default)]
+        count: u32,
+        #[serde(default)]
+        done: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct ItemV3 {
+        title: String,
+        #[serde(default)]
+        done: bool,
+        #[serde(default = "default_note")]
+        note: String,
+    }
+
+    // bincode keeps the slot of the removed "count"
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct ItemV3Bincode {
+        title: String,
+        count: u32,
+        done: bool,
+        note: String,
+    }
+
+    fn default_note() -> String {
+        "-".to_string()
+    }
+
+    impl Versioned for ItemV1 {
+        const VERSION: u16 = 1;
+    }
+
+    impl Versioned for ItemV2 {
+        const VERSION: u16 = 2;
+
+        fn upgrade(version: u16, payload: &[u8]) -> Result<Self, Error> {
+            match version {
+                1 => {
+                    let v1: ItemV1 = bincode::deserialize(payload)?;
+                    Ok(ItemV2 {
+                        title: v1.title,
+                        count: v1.count,
+                        done: false,
+                    })
+                }
+                _ => Err(Error::msg(format!("unknown version {}", version))),
+            }
+        }
+    }
+
+    impl Versioned for ItemV3 {
+        const VERSION: u16 = 3;
+    }
+
+    impl Versioned for ItemV3Bincode {
+        const VERSION: u16 = 3;
+
+        fn upgrade(version: u16, payload: &[u8]) -> Result<Self, Error> {
+            let v2 = match version {
+                1 => ItemV2::upgrade(1, payload)?,
+                _ => bincode::deserialize(payload)?,
+            };
+            Ok(ItemV3Bincode {
+                title: v2.title,
+                count: v2.count,
+                done: v2.done,
+                note: default_note(),
+            })
+        }
+    }
+
+    fn v1() -> ItemV1 {
+        ItemV1 {
+            title: "a".to_string(),
+            count: 3,
+        }
+    }
+
+    fn v2() -> ItemV2 {
+        ItemV2 {
+            title: "b".to_string(),
+            count: 4,
+            done: true,
+        }
+    }
+
+    fn v3() -> ItemV3 {
+        ItemV3 {
+            title: "c".to_string(),
+            done: true,
+            note: "n".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_msgpack_added_field() {
+        // an older writer: the new field is defaulted
+        let out: ItemV2 = from_msgpack(&to_msgpack(&v1()).unwrap()).unwrap();
+        assert_eq!(
+            out,
+            ItemV2 {
+                title: "a".to_string(),
+                count: 3,
+                done: false
+            }
+        );
+        // a newer writer: the new field is skipped
+        let out: ItemV1 = from_msgpack(&to_msgpack(&v2()).unwrap()).unwrap();
+        assert_eq!(
+            out,
+            ItemV1 {
+                title: "b".to_string(),
+                count: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_msgpack_removed_field() {
+        // v3 does not write "count", v2 defaults it
+        let out: ItemV2 = from_msgpack(&to_msgpack(&v3()).unwrap()).unwrap();
+        assert_eq!(
+            out,
+            ItemV2 {
+                title: "c".to_string(),
+                count: 0,
+                done: true
+            }
+        );
+        // v3 skips "count" of v2 and defaults the note with its function
+        let out: ItemV3 = from_msgpack(&to_msgpack(&v2()).unwrap()).unwrap();
+        assert_eq!(
+            out,
+            ItemV3 {
+                title: "b".to_string(),
+                done: true,
+                note: "-".to_string()
+            }
+        );
+        // v1 has no default for "count"
+        assert!(from_msgpack::<ItemV1>(&to_msgpack(&v3()).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_bincode_added_field() {
+        let out: ItemV2 = from_bincode(&to_bincode(&v1()).unwrap()).unwrap();
+        assert_eq!(
+            out,
+            ItemV2 {
+                title: "a".to_string(),
+                count: 3,
+                done: false
+            }
+        );
+        let out: ItemV1 = from_bincode(&to_bincode(&v2()).unwrap()).unwrap();
+        assert_eq!(
+            out,
+            ItemV1 {
+                title: "b".to_string(),
+                count: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_bincode_removed_field() {
+        let v3 = ItemV3Bincode {
+            title: "c".to_string(),
+            count: 0,
+            done: true,
+            note: "n".to_string(),
+        };
+        let bytes = to_bincode(&v3).unwrap();
+        let out: ItemV1 = from_bincode(&bytes).unwrap();
+        assert_eq!(
+            out,
+            ItemV1 {
+                title: "c".to_string(),
+                count: 0
+            }
+        );
+        let out: ItemV2 = from_bincode(&bytes).unwrap();
+        assert!(out.done);
+        // two versions up, through the upgrade of v2
+        let out: ItemV3Bincode = from_bincode(&to_bincode(&v1()).unwrap()).unwrap();
+        assert_eq!(
+            out,
+            ItemV3Bincode {
+                title: "a".to_string(),
+                count: 3,
+                done: false,
+                note: "-".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_payload() {
+        assert!(from_bincode::<ItemV1>(&[1]).is_err());
+        // an older version the type can not upgrade from
+        let mut bytes = to_bincode(&v1()).unwrap();
+        bytes[0] = 0;
+        assert!(from_bincode::<ItemV1>(&bytes).is_err());
+    }
+}
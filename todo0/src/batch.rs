@@ -0,0 +1,71 @@
+use crate::i18n::tr;
+
+// a command of a script with the line it starts on
+pub type ScriptCommand = (usize, Vec<String>);
+
+// split a script into commands, one per line or separated by ";". words are
+// split at whitespace, quotes keep words with spaces or ";" together and "#"
+// in place of a command comments out the rest of the line, so tags still work.
+pub fn parse_script(script: &str) -> Result<Vec<ScriptCommand>, String> {
+    let mut commands = vec![];
+    for (idx, line) in script.lines().enumerate() {
+        let line_no = idx + 1;
+        let mut words: Vec<String> = vec![];
+        let mut word: Option<String> = None;
+        let mut quote: Option<char> = None;
+        for c in line.chars() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+                (None, '"') | (None, '\'') => {
+                    quote = Some(c);
+                    word.get_or_insert_with(String::new);
+                }
+                (None, '#') if word.is_none() && words.is_empty() => break,
+                (None, ';') => {
+                    words.extend(word.take());
+                    if !words.is_empty() {
+                        commands.push((line_no, std::mem::take(&mut words)));
+                    }
+                }
+                (None, c) if c.is_whitespace() => words.extend(word.take()),
+                (None, c) => word.get_or_insert_with(String::new).push(c),
+            }
+        }
+        if quote.is_some() {
+            return Err(tr!("unclosed_quote", line = line_no));
+        }
+        words.extend(word.take());
+        if !words.is_empty() {
+            commands.push((line_no, words));
+        }
+    }
+    Ok(commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_commands() {
+        let script = "# release\nadd \"tag v1.2; push\" #work\n\ncheck 2; del 3 ;\nadd ''\n";
+        assert_eq!(
+            parse_script(script).unwrap(),
+            vec![
+                (2, words(&["add", "tag v1.2; push", "#work"])),
+                (4, words(&["check", "2"])),
+                (4, words(&["del", "3"])),
+                (5, words(&["add", ""])),
+            ]
+        );
+        assert_eq!(
+            parse_script("add 'x").unwrap_err(),
+            "line 1: unclosed quote"
+        );
+    }
+}
@@ -1,52 +1,87 @@
-use crate::{help::*, todo::*};
+use crate::i18n::tr;
+use crate::{
+    config::Config, crypt::Secret, date::*, deps::*, file::*, help::*, hook::*, ical::*,
+    markdown::*, org::*, plugin::*, scan::*, snapshot::*, stats::*, template::*, term::*, todo::*,
+    track::*, urgency::*, watch::*,
+};
 
-pub fn cmd_add(todo_list: &mut TodoList, args: &[String]) -> Result<(), String> {
+use std::io::IsTerminal;
+
+use chrono::{Duration, Local};
+
+pub fn cmd_add(todo_list: &mut TodoList, config: &Config, args: &[String]) -> Result<(), String> {
+    let (args, dry_run) = take_flag(args, "--dry-run");
+    let args = &args[..];
     if args.len() == 0 {
-        return Err(format!("Invalid arguments. {}", short_help()));
+        return Err(tr!("invalid_arguments", help = short_help()));
     };
+    if dry_run {
+        return print_interpretation(config, args);
+    }
     let index = args[0].parse::<usize>();
     match index {
         Err(_) => {
-            let task = parse_task(args)?;
-            map_result(todo_list.add(None, task))?;
-            println!("added 1 task");
+            let task = parse_task(config, args)?;
+            with_hooks(config, HookEvent::Add, &task, || {
+                todo_list.add(None, task.clone())
+            })?;
+            println!("{}", tr!("tasks_added", count = 1));
         }
         Ok(index) => {
-            let task = parse_task(&args[1..])?;
-            map_result(todo_list.add(Some(Position::AtIndex(index - 1)), task))?;
-            println!("added 1 task");
+            // the position may be right after the last task
+            let max = todo_list.items.len() + 1;
+            if index == 0 || index > max {
+                return Err(tr!("index_out_of_range", index = index, max = max));
+            }
+            let task = parse_task(config, &args[1..])?;
+            with_hooks(config, HookEvent::Add, &task, || {
+                todo_list.add(Some(Position::AtIndex(index - 1)), task.clone())
+            })?;
+            println!("{}", tr!("tasks_added", count = 1));
         }
     };
     Ok(())
 }
 
-pub fn cmd_edit(todo_list: &mut TodoList, args: &[String]) -> Result<(), String> {
+pub fn cmd_edit(todo_list: &mut TodoList, config: &Config, args: &[String]) -> Result<(), String> {
+    let (args, dry_run) = take_flag(args, "--dry-run");
+    let args = &args[..];
     if args.len() == 0 {
-        return Err(format!("Invalid arguments. {}", short_help()));
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    if dry_run {
+        return print_interpretation(config, args);
     }
     let index = args[0].parse::<usize>();
     match index {
         Err(_) => {
-            let task = parse_task(args)?;
+            let task = parse_task(config, args)?;
             let len = todo_list.items.len();
             let index = if len > 0 { len } else { 1 };
-            map_result(todo_list.edit(Some(Position::AtIndex(index - 1)), task))?;
-            println!("edited 1 task");
+            edit_title_at(todo_list, config, index, task)?;
+            println!("{}", tr!("tasks_edited", count = 1));
         }
         Ok(index) => {
-            let task = parse_task(&args[1..])?;
-            map_result(todo_list.edit(Some(Position::AtIndex(index - 1)), task))?;
-            println!("edited 1 task");
+            let task = parse_task(config, &args[1..])?;
+            edit_title_at(todo_list, config, index, task)?;
+            println!("{}", tr!("tasks_edited", count = 1));
         }
     };
     Ok(())
 }
 
-pub fn cmd_del(todo_list: &mut TodoList, args: &[String]) -> Result<(), String> {
+pub fn cmd_del(todo_list: &mut TodoList, config: &Config, args: &[String]) -> Result<(), String> {
     if args.len() == 0 {
-        return Err(format!("Invalid arguments. {}", short_help()));
+        return Err(tr!("invalid_arguments", help = short_help()));
     }
     let indexes = parse_args_as_indexes(todo_list.items.len(), args)?;
+    let tasks: Vec<_> = indexes
+        .iter()
+        .map(|idx| todo_list.items[*idx - 1].clone())
+        .collect();
+    for task in tasks.iter() {
+        run_hooks(&config.hooks, HookStage::Pre, HookEvent::Del, task)?;
+    }
     let positions: Vec<_> = indexes
         .iter()
         .map(|idx| Some(Position::AtIndex(*idx - 1)))
@@ -62,6 +97,10 @@ pub fn cmd_del(todo_list: &mut TodoList, args: &[String]) -> Result<(), String>
         .collect();
     results.sort();
 
+    for task in tasks.iter() {
+        run_hooks(&config.hooks, HookStage::Post, HookEvent::Del, task)?;
+    }
+
     let mut count: usize = 0;
     let mut last: usize = 0;
     for id in results {
@@ -70,13 +109,13 @@ pub fn cmd_del(todo_list: &mut TodoList, args: &[String]) -> Result<(), String>
         }
         last = id
     }
-    println!("deleted {} tasks", count);
+    println!("{}", tr!("tasks_deleted", count = count));
     Ok(())
 }
 
-pub fn cmd_check(todo_list: &mut TodoList, args: &[String]) -> Result<(), String> {
+pub fn cmd_check(todo_list: &mut TodoList, config: &Config, args: &[String]) -> Result<(), String> {
     if args.len() == 0 {
-        return Err(format!("Invalid arguments. {}", short_help()));
+        return Err(tr!("invalid_arguments", help = short_help()));
     }
     let mut count: usize = 0;
     let indexes = parse_args_as_indexes(todo_list.items.len(), args)?;
@@ -84,22 +123,35 @@ pub fn cmd_check(todo_list: &mut TodoList, args: &[String]) -> Result<(), String
         let task = &todo_list.items[index - 1];
         if !task.done {
             count += 1;
-            let mut task = task.clone();
-            task.done = true;
-            map_result(todo_list.edit(Some(Position::AtIndex(index - 1)), task))?;
+            let blockers = open_blockers(todo_list, task);
+            if !blockers.is_empty() {
+                let titles: Vec<_> = blockers
+                    .iter()
+                    .map(|x| format!("\"{}\"", x.title))
+                    .collect();
+                let blockers = titles.join(", ");
+                eprintln!(
+                    "{}",
+                    tr!("still_blocked", title = task.title, blockers = blockers)
+                );
+            }
+            let task = config
+                .workflow
+                .set_state(task, config.workflow.final_state());
+            edit_at(todo_list, config, HookEvent::Check, index, task)?;
         }
     }
-    println!(
-        "marked {} task{} as done",
-        count,
-        if count != 1 { "s" } else { "" }
-    );
+    println!("{}", tr!("tasks_checked", count = count));
     Ok(())
 }
 
-pub fn cmd_uncheck(todo_list: &mut TodoList, args: &[String]) -> Result<(), String> {
+pub fn cmd_uncheck(
+    todo_list: &mut TodoList,
+    config: &Config,
+    args: &[String],
+) -> Result<(), String> {
     if args.len() == 0 {
-        return Err(format!("Invalid arguments. {}", short_help()));
+        return Err(tr!("invalid_arguments", help = short_help()));
     }
     let mut count: usize = 0;
     let indexes = parse_args_as_indexes(todo_list.items.len(), args)?;
@@ -107,19 +159,719 @@ pub fn cmd_uncheck(todo_list: &mut TodoList, args: &[String]) -> Result<(), Stri
         let task = &todo_list.items[index - 1];
         if task.done {
             count += 1;
-            let mut task = task.clone();
-            task.done = false;
-            map_result(todo_list.edit(Some(Position::AtIndex(index - 1)), task))?;
+            let task = config.workflow.set_state(task, config.workflow.initial());
+            edit_at(todo_list, config, HookEvent::Uncheck, index, task)?;
+        }
+    }
+    println!("{}", tr!("tasks_unchecked", count = count));
+    Ok(())
+}
+
+pub fn cmd_list(todo_list: &TodoList, config: &Config, args: &[String]) -> Result<(), String> {
+    print_paged(&render_list(todo_list, config, args)?);
+    Ok(())
+}
+
+// show the list and show it again whenever another process changes the file,
+// until interrupted
+pub fn cmd_follow(
+    file_path: &std::path::Path,
+    secret: Option<&Secret>,
+    config: &Config,
+    args: &[String],
+) -> Result<(), String> {
+    let watcher = FileWatcher::new(file_path)?;
+    let clear = std::io::stdout().is_terminal();
+    loop {
+        // the file may be read in the middle of a save of another editor or
+        // sync tool, so the last list stays until the next change
+        let todo_list = match load_from_file(file_path, secret) {
+            Ok(todo_list) => todo_list,
+            Err(err) => {
+                eprintln!("{}", err);
+                watcher.wait(None)?;
+                continue;
+            }
+        };
+        if clear {
+            print!("\x1b[2J\x1b[H");
+        }
+        match todo_list.items.len() {
+            0 => println!("{}", tr!("no_tasks")),
+            _ => println!("{}", render_list(&todo_list, config, args)?),
+        }
+        watcher.wait(None)?;
+    }
+}
+
+fn render_list(todo_list: &TodoList, config: &Config, args: &[String]) -> Result<String, String> {
+    let (args, mine) = take_flag(args, "--mine");
+    let (args, truncate) = take_flag(&args, "--truncate");
+    let mut format = None;
+    let mut sort = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = args.next(),
+            "--sort" => sort = args.next(),
+            _ => return Err(tr!("invalid_arguments", help = short_help())),
+        }
+    }
+    let user = if mine {
+        Some(current_user(config)?)
+    } else {
+        None
+    };
+    let filter = |task: &Task| user.is_none() || task.assignee == user;
+    if let Some(format) = format {
+        let mut list = todo_list.clone();
+        list.items.retain(filter);
+        let out = render_with_plugin(&list, config, format)?;
+        return Ok(out.trim_end().to_string());
+    }
+    match sort.map(|x| x.as_str()) {
+        None => Ok(todo_list.format_filter(list_options(truncate), filter)),
+        Some("urgency") => {
+            let indexes: Vec<usize> = (0..todo_list.items.len())
+                .filter(|idx| filter(&todo_list.items[*idx]))
+                .collect();
+            let now = Local::now();
+            let width = output_width();
+            Ok(format_by_urgency(
+                todo_list,
+                &indexes,
+                &config.urgency,
+                now,
+                width,
+            ))
         }
+        Some(sort) => Err(tr!("unknown_sort", sort = format!("{:?}", sort))),
     }
+}
+
+pub fn cmd_assign(
+    todo_list: &mut TodoList,
+    config: &Config,
+    args: &[String],
+) -> Result<(), String> {
+    if args.len() < 2 {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let (user, args) = args.split_last().unwrap();
+    let user = user.trim_start_matches('@');
+    assign(todo_list, config, args, Some(user))?;
+    Ok(())
+}
+
+pub fn cmd_unassign(
+    todo_list: &mut TodoList,
+    config: &Config,
+    args: &[String],
+) -> Result<(), String> {
+    if args.is_empty() {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    assign(todo_list, config, args, None)
+}
+
+fn assign(
+    todo_list: &mut TodoList,
+    config: &Config,
+    args: &[String],
+    user: Option<&str>,
+) -> Result<(), String> {
+    let indexes = parse_args_as_indexes(todo_list.items.len(), args)?;
+    for index in indexes.iter() {
+        let task = Task {
+            assignee: user.map(String::from),
+            ..todo_list.items[index - 1].clone()
+        };
+        edit_at(todo_list, config, HookEvent::Edit, *index, task)?;
+    }
+    match user {
+        Some(user) => println!(
+            "{}",
+            tr!("tasks_assigned", count = indexes.len(), user = user)
+        ),
+        None => println!("{}", tr!("tasks_unassigned", count = indexes.len())),
+    }
+    Ok(())
+}
+
+pub fn cmd_prio(todo_list: &mut TodoList, config: &Config, args: &[String]) -> Result<(), String> {
+    if args.len() < 2 {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let (priority, args) = args.split_last().unwrap();
+    let priority = Priority::parse(priority).ok_or(tr!(
+        "invalid_priority",
+        priority = format!("{:?}", priority)
+    ))?;
+    let indexes = parse_args_as_indexes(todo_list.items.len(), args)?;
+    for index in indexes.iter() {
+        let task = Task {
+            priority,
+            ..todo_list.items[index - 1].clone()
+        };
+        edit_at(todo_list, config, HookEvent::Edit, *index, task)?;
+    }
+    let count = indexes.len();
     println!(
-        "marked {} task{} as not done",
-        count,
-        if count != 1 { "s" } else { "" }
+        "{}",
+        tr!("priority_set", count = count, priority = priority.name())
+    );
+    Ok(())
+}
+
+pub fn cmd_stats(todo_list: &TodoList, config: &Config, args: &[String]) -> Result<(), String> {
+    let now = Local::now();
+    let mut until = now;
+    let mut since = now - Duration::days(13);
+    let mut json = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--since" | "--until" => {
+                let value = args.next().map(|x| x.as_str()).unwrap_or("");
+                let date = parse_date(value, now)
+                    .ok_or(tr!("invalid_date", date = format!("{:?}", value)))?;
+                match arg.as_str() {
+                    "--since" => since = date,
+                    _ => until = date,
+                }
+            }
+            "--json" => json = true,
+            _ => return Err(tr!("invalid_arguments", help = short_help())),
+        }
+    }
+
+    let stats = compute(
+        todo_list,
+        &config.workflow,
+        since.date_naive(),
+        until.date_naive(),
     );
+    if json {
+        let out = serde_json::to_string_pretty(&stats)
+            .map_err(|err| tr!("encode_json_failed", error = err))?;
+        println!("{}", out);
+    } else {
+        println!("{}", format_stats(&stats));
+    }
     Ok(())
 }
 
+// sync the comments of the dir into its own list, see scan::get_scan_file_path
+pub fn cmd_scan(config: &Config, args: &[String]) -> Result<(), String> {
+    if args.len() != 1 {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let dir = std::path::Path::new(&args[0]);
+    let comments = scan_dir(dir)?;
+    let file_path = get_scan_file_path(dir);
+    let mut todo_list = load_from_file(&file_path, None)?;
+    let summary = sync(&mut todo_list, &config.workflow, &comments);
+    let found = tr!(
+        "comments_found",
+        count = comments.len(),
+        added = summary.added,
+        updated = summary.updated,
+        reopened = summary.reopened,
+        done = summary.done
+    );
+    println!("{}", found);
+    println!("{}", todo_list.format(list_options(false)));
+    save_to_file(&file_path, todo_list, None)
+}
+
+pub fn cmd_plugins(config: &Config) -> Result<(), String> {
+    let paths = plugin_paths(config)?;
+    if paths.is_empty() {
+        println!(
+            "{}",
+            tr!("no_plugins", dir = format!("{:?}", config.plugin_dir()))
+        );
+        return Ok(());
+    }
+    for (path, info) in paths.iter().zip(describe_plugins(&paths)?) {
+        println!("{} ({})", info.name, path.display());
+        for command in info.commands.iter() {
+            let line = format!("  {}  {}", pad(&command.name, 12), command.help);
+            println!("{}", line.trim_end());
+        }
+        for renderer in info.renderers.iter() {
+            println!("  list --format {}", renderer);
+        }
+    }
+    Ok(())
+}
+
+pub fn cmd_template(
+    todo_list: &mut TodoList,
+    config: &Config,
+    file_path: &std::path::Path,
+    secret: Option<&Secret>,
+    args: &[String],
+) -> Result<(), String> {
+    let mut vars = vec![];
+    let mut rest = vec![];
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--var" => vars.push(parse_var(iter.next().map(|x| x.as_str()).unwrap_or(""))?),
+            _ => rest.push(arg.clone()),
+        }
+    }
+    let templates_path = get_templates_path(file_path);
+    let mut templates = load_templates(&templates_path, secret)?;
+    match (rest.first().map(|x| x.as_str()), rest.get(1)) {
+        (Some("list"), None) => {
+            for (name, template) in templates.iter() {
+                println!(
+                    "{}",
+                    tr!("template", name = name, count = template.tasks.len())
+                );
+            }
+            Ok(())
+        }
+        (Some("save"), Some(name)) if rest.len() > 2 => {
+            let indexes = parse_args_as_indexes(todo_list.items.len(), &rest[2..])?;
+            let template = capture(todo_list, &indexes, &vars);
+            let count = template.tasks.len();
+            println!("{}", tr!("template_saved", name = name, count = count));
+            templates.insert(name.clone(), template);
+            save_templates(&templates_path, &templates, secret)
+        }
+        (Some("apply"), Some(name)) if rest.len() == 2 => {
+            let template = templates
+                .get(name)
+                .ok_or(tr!("unknown_template", name = format!("{:?}", name)))?;
+            let tasks = instantiate(template, &vars)?;
+            for task in tasks.iter() {
+                with_hooks(config, HookEvent::Add, task, || {
+                    todo_list.add(None, task.clone())
+                })?;
+            }
+            println!("{}", tr!("tasks_added", count = tasks.len()));
+            Ok(())
+        }
+        (Some("del"), Some(name)) if rest.len() == 2 => {
+            templates
+                .remove(name)
+                .ok_or(tr!("unknown_template", name = format!("{:?}", name)))?;
+            println!("{}", tr!("template_deleted", name = name));
+            save_templates(&templates_path, &templates, secret)
+        }
+        _ => Err(tr!("invalid_arguments", help = short_help())),
+    }
+}
+
+pub fn cmd_export(todo_list: &TodoList, config: &Config, args: &[String]) -> Result<(), String> {
+    let format = match args {
+        [] => "ics",
+        [flag, format] if flag == "--format" => format.as_str(),
+        _ => return Err(tr!("invalid_arguments", help = short_help())),
+    };
+    match format {
+        "ics" => print!("{}", export_ics(todo_list, &config.workflow)),
+        "org" => print!("{}", export_org(todo_list, &config.workflow)),
+        _ => {
+            return Err(tr!(
+                "unknown_export_format",
+                format = format!("{:?}", format)
+            ))
+        }
+    }
+    Ok(())
+}
+
+// tasks which are already in the list are updated with the fields the file
+// stores, the others are appended
+pub fn cmd_import(
+    todo_list: &mut TodoList,
+    config: &Config,
+    args: &[String],
+) -> Result<(), String> {
+    if args.len() != 1 {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let file_path = std::path::Path::new(&args[0]);
+    let content = std::fs::read_to_string(file_path).map_err(|err| {
+        tr!(
+            "read_file_failed",
+            file = format!("{:?}", file_path),
+            error = err
+        )
+    })?;
+    let tasks = match file_path.extension().and_then(|x| x.to_str()) {
+        Some("ics") => import_ics(&content, &config.workflow),
+        Some("org") => Ok(import_org(&content, &config.workflow)),
+        _ => Err(tr!(
+            "unknown_import_format",
+            file = format!("{:?}", file_path)
+        )),
+    }
+    .map_err(|err| {
+        tr!(
+            "import_failed",
+            file = format!("{:?}", file_path),
+            error = err
+        )
+    })?;
+    let update: fn(&Task, Task) -> Task = match file_path.extension() {
+        Some(x) if x == "org" => update_from_org,
+        _ => update_from_ics,
+    };
+    let (mut added, mut updated) = (0, 0);
+    for task in tasks {
+        let res = match todo_list.items.iter().find(|x| x.id == task.id) {
+            Some(old) => {
+                let task = update(old, task);
+                todo_list.edit(None, task)
+            }
+            None => todo_list.add(None, task),
+        };
+        match res {
+            Ok(ActionResult::Updated(_)) => updated += 1,
+            res => {
+                map_result(res)?;
+                added += 1
+            }
+        }
+    }
+    let imported = tr!(
+        "tasks_imported",
+        count = added + updated,
+        added = added,
+        updated = updated
+    );
+    println!("{}", imported);
+    Ok(())
+}
+
+pub fn cmd_mv_state(
+    todo_list: &mut TodoList,
+    config: &Config,
+    args: &[String],
+) -> Result<(), String> {
+    if args.len() < 2 {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let (state, args) = args.split_last().unwrap();
+    let indexes = parse_args_as_indexes(todo_list.items.len(), args)?;
+    for index in indexes.iter() {
+        let task = config
+            .workflow
+            .move_to(&todo_list.items[index - 1], state)?;
+        edit_at(todo_list, config, HookEvent::Move, *index, task)?;
+    }
+    println!(
+        "{}",
+        tr!("tasks_moved", count = indexes.len(), state = state)
+    );
+    Ok(())
+}
+
+pub fn cmd_board(todo_list: &TodoList, config: &Config) -> Result<(), String> {
+    println!("{}", config.workflow.format_board(todo_list));
+    Ok(())
+}
+
+pub fn cmd_start(todo_list: &mut TodoList, args: &[String]) -> Result<(), String> {
+    if args.len() != 1 {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let indexes = parse_args_as_indexes(todo_list.items.len(), args)?;
+    let stopped =
+        start(todo_list, indexes[0] - 1, Local::now()).map_err(|err| format!("{}", err))?;
+    if let Some(task) = stopped {
+        println!("{}", tr!("task_stopped", title = task.title));
+    }
+    let title = &todo_list.items[indexes[0] - 1].title;
+    println!("{}", tr!("task_started", title = title));
+    Ok(())
+}
+
+pub fn cmd_stop(todo_list: &mut TodoList, args: &[String]) -> Result<(), String> {
+    if !args.is_empty() {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let stopped = stop(todo_list, Local::now()).map_err(|err| format!("{}", err))?;
+    match stopped {
+        None => return Err(tr!("no_task_started")),
+        Some(task) => {
+            let total = format_duration(tracked(&task, None, Local::now()));
+            println!(
+                "{}",
+                tr!("task_stopped_total", title = task.title, total = total)
+            )
+        }
+    }
+    Ok(())
+}
+
+pub fn cmd_report(todo_list: &TodoList, args: &[String]) -> Result<(), String> {
+    let mut since = None;
+    let mut by = GroupBy::Task;
+    let mut csv = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--since" => {
+                let value = args.next().map(|x| x.as_str()).unwrap_or("");
+                let date = parse_date(value, Local::now())
+                    .ok_or(tr!("invalid_date", date = format!("{:?}", value)))?;
+                since = Some(date);
+            }
+            "--by" => {
+                by = match args.next().map(|x| x.as_str()) {
+                    Some("task") => GroupBy::Task,
+                    Some("tag") => GroupBy::Tag,
+                    _ => return Err(tr!("invalid_arguments", help = short_help())),
+                }
+            }
+            "--csv" => csv = true,
+            _ => return Err(tr!("invalid_arguments", help = short_help())),
+        }
+    }
+
+    let now = Local::now();
+    let rows = report(todo_list, since, by, now);
+    if csv {
+        print!("{}", format_report_csv(&rows, by));
+    } else {
+        let total = todo_list
+            .items
+            .iter()
+            .map(|task| tracked(task, since, now))
+            .sum();
+        println!("{}", format_report(&rows, total));
+    }
+    Ok(())
+}
+
+pub fn cmd_block(todo_list: &mut TodoList, args: &[String]) -> Result<(), String> {
+    let args: Vec<_> = args.iter().filter(|x| *x != "on").cloned().collect();
+    if args.len() != 2 {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let indexes = parse_args_as_indexes(todo_list.items.len(), &args)?;
+    map_result(block(todo_list, indexes[0] - 1, indexes[1] - 1))?;
+    let title = &todo_list.items[indexes[0] - 1].title;
+    let blocker = &todo_list.items[indexes[1] - 1].title;
+    println!("{}", tr!("task_blocked", title = title, blocker = blocker));
+    Ok(())
+}
+
+pub fn cmd_unblock(todo_list: &mut TodoList, args: &[String]) -> Result<(), String> {
+    let args: Vec<_> = args.iter().filter(|x| *x != "on").cloned().collect();
+    if args.is_empty() || args.len() > 2 {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let indexes = parse_args_as_indexes(todo_list.items.len(), &args)?;
+    map_result(unblock(
+        todo_list,
+        indexes[0] - 1,
+        indexes.get(1).map(|idx| idx - 1),
+    ))?;
+    println!("{}", tr!("tasks_unblocked", count = 1));
+    Ok(())
+}
+
+pub fn cmd_ready(todo_list: &TodoList) -> Result<(), String> {
+    let opts = list_options(false);
+    println!(
+        "{}",
+        todo_list.format_filter(opts, |task| is_ready(todo_list, task))
+    );
+    Ok(())
+}
+
+// the tasks of a snapshot are only counted when it is cheap: decrypting a
+// snapshot derives its key first, so for an encrypted list it takes --verbose
+pub fn cmd_snapshots(
+    file_path: &std::path::Path,
+    secret: Option<&Secret>,
+    args: &[String],
+) -> Result<(), String> {
+    let (args, verbose) = take_flag(args, "--verbose");
+    if !args.is_empty() {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let names = list_snapshots(file_path)?;
+    if names.is_empty() {
+        println!("{}", tr!("no_snapshots"));
+    }
+    let index_width = names.len().to_string().len();
+    for (idx, name) in names.iter().enumerate() {
+        let index = format!("{:>width$}", idx + 1, width = index_width);
+        if secret.is_some() && !verbose {
+            println!("{}", tr!("snapshot_name", index = index, name = name));
+            continue;
+        }
+        let (_, list) = load_snapshot(file_path, name, secret)?;
+        let done = list.items.iter().filter(|x| x.done).count();
+        let line = tr!(
+            "snapshot",
+            index = index,
+            name = name,
+            count = list.items.len(),
+            done = done
+        );
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+// replace the list with the snapshot, the list before is kept as a snapshot too
+pub fn cmd_restore(
+    todo_list: &mut TodoList,
+    file_path: &std::path::Path,
+    secret: Option<&Secret>,
+    args: &[String],
+) -> Result<(), String> {
+    if args.len() != 1 {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let (name, list) = load_snapshot(file_path, &args[0], secret)?;
+    *todo_list = list;
+    let count = todo_list.items.len();
+    println!("{}", tr!("tasks_restored", count = count, name = name));
+    Ok(())
+}
+
+// what changed in the list since the snapshot
+pub fn cmd_diff(
+    todo_list: &TodoList,
+    file_path: &std::path::Path,
+    secret: Option<&Secret>,
+    args: &[String],
+) -> Result<(), String> {
+    if args.len() != 1 {
+        return Err(tr!("invalid_arguments", help = short_help()));
+    }
+    let (name, snapshot) = load_snapshot(file_path, &args[0], secret)?;
+    let changes = diff(&snapshot, todo_list);
+    if changes.is_empty() {
+        println!("{}", tr!("no_changes", name = name));
+    }
+    for change in changes.iter() {
+        println!("{}", format_change(change));
+    }
+    Ok(())
+}
+
+// show the ready task with the highest urgency score
+pub fn cmd_next(todo_list: &TodoList, config: &Config) -> Result<(), String> {
+    let now = Local::now();
+    let idx = match next(todo_list, &config.urgency, now) {
+        Some(idx) => idx,
+        None => {
+            println!("{}", tr!("nothing_ready"));
+            return Ok(());
+        }
+    };
+    let task = &todo_list.items[idx];
+    let score = score(todo_list, task, &config.urgency, now);
+    println!("{}. {}", idx + 1, task.title);
+    let parts = format_parts(&score);
+    let total = format!("{:.1}", score.total);
+    match parts.is_empty() {
+        true => println!("{}", tr!("urgency", total = total)),
+        false => println!("{}", tr!("urgency_parts", total = total, parts = parts)),
+    }
+    Ok(())
+}
+
+pub fn cmd_graph(todo_list: &TodoList) -> Result<(), String> {
+    println!("{}", format_dot(todo_list));
+    Ok(())
+}
+
+// run the pre hooks, apply the change, then run the post hooks
+// the commands which change what a markdown file does not store, it only keeps
+// the title and the checkbox of a task
+const NOT_IN_MARKDOWN: [&str; 8] = [
+    "assign", "unassign", "prio", "start", "stop", "block", "unblock", "mv-state",
+];
+
+// the commands which change what an org file does not store, the state is
+// stored as the TODO keyword of the heading
+const NOT_IN_ORG: [&str; 6] = ["assign", "unassign", "start", "stop", "block", "unblock"];
+
+// reject a command whose change the data file can not store, instead of
+// reporting a change which is lost when the file is saved
+pub fn check_file_command(config: &Config, args: &[String]) -> Result<(), String> {
+    let file_path = config.file_path();
+    let command = args[0].as_str();
+    if is_markdown_file(&file_path) && NOT_IN_MARKDOWN.contains(&command) {
+        return Err(tr!("not_in_markdown"));
+    }
+    if is_org_file(&file_path) && NOT_IN_ORG.contains(&command) {
+        return Err(tr!("not_in_org"));
+    }
+    if is_org_file(&file_path) && command == "mv-state" && args.len() > 2 {
+        let state = args[args.len() - 1].to_lowercase();
+        let content = std::fs::read_to_string(&file_path).unwrap_or_default();
+        if state != config.workflow.final_state() && !open_states(&content).contains(&state) {
+            return Err(tr!("no_org_keyword", state = state));
+        }
+    }
+    Ok(())
+}
+
+fn with_hooks(
+    config: &Config,
+    event: HookEvent,
+    task: &Task,
+    action: impl FnOnce() -> Result<ActionResult, Error>,
+) -> Result<(), String> {
+    run_hooks(&config.hooks, HookStage::Pre, event, task)?;
+    map_result(action())?;
+    run_hooks(&config.hooks, HookStage::Post, event, task)
+}
+
+// replace the task at the 1-based index, hooks see the task with its final id
+fn edit_at(
+    todo_list: &mut TodoList,
+    config: &Config,
+    event: HookEvent,
+    index: usize,
+    task: Task,
+) -> Result<(), String> {
+    let prev = todo_list
+        .items
+        .get(index - 1)
+        .ok_or(format!("{}", Error::NotFound))?;
+    let task = Task {
+        id: prev.id,
+        ..task
+    };
+    with_hooks(config, event, &task, || {
+        todo_list.edit(Some(Position::AtIndex(index - 1)), task.clone())
+    })
+}
+
+// change only the title and the due date of the task at the 1-based index, so
+// other fields like the tracked time and the blockers are kept
+fn edit_title_at(
+    todo_list: &mut TodoList,
+    config: &Config,
+    index: usize,
+    task: Task,
+) -> Result<(), String> {
+    let prev = todo_list
+        .items
+        .get(index - 1)
+        .ok_or(format!("{}", Error::NotFound))?;
+    let task = Task {
+        title: task.title,
+        due: task.due.or(prev.due),
+        ..prev.clone()
+    };
+    edit_at(todo_list, config, HookEvent::Edit, index, task)
+}
+
 fn map_result(res: Result<ActionResult, Error>) -> Result<(), String> {
     match res {
         Ok(_) => Ok(()),
@@ -127,25 +879,237 @@ fn map_result(res: Result<ActionResult, Error>) -> Result<(), String> {
     }
 }
 
-fn parse_task(args: &[String]) -> Result<Task, String> {
+// a markdown file does not store due dates, so the words stay in the title
+// there instead of being lost
+fn extract_task_due(config: &Config, args: &[String]) -> Extracted {
+    if is_markdown_file(&config.file_path()) {
+        return Extracted {
+            title: args.join(" "),
+            due: None,
+            phrase: String::new(),
+        };
+    }
+    extract_due(args, Local::now())
+}
+
+fn parse_task(config: &Config, args: &[String]) -> Result<Task, String> {
     if args.len() == 0 {
-        Err(format!("Missing task title. {}", short_help()))
+        Err(tr!("missing_title", help = short_help()))
     } else {
-        let title = args[0..].join(" ");
-        Ok(Task::new(&title))
+        let extracted = extract_task_due(config, args);
+        let mut task = Task::new(&extracted.title);
+        task.due = extracted.due;
+        Ok(task)
+    }
+}
+
+// show how the title and the due date are read from the arguments
+fn print_interpretation(config: &Config, args: &[String]) -> Result<(), String> {
+    let args = match args[0].parse::<usize>() {
+        Ok(_) => &args[1..],
+        Err(_) => args,
+    };
+    let extracted = extract_task_due(config, args);
+    println!("{}", tr!("interpreted_title", title = extracted.title));
+    match extracted.due {
+        None => println!("{}", tr!("interpreted_no_due")),
+        Some(due) => {
+            let due = format_due(due);
+            let phrase = extracted.phrase;
+            println!("{}", tr!("interpreted_due", due = due, phrase = phrase))
+        }
+    }
+    Ok(())
+}
+
+// the options to print a list to the terminal
+fn list_options(truncate: bool) -> FormatOptions {
+    FormatOptions {
+        use_color: true,
+        width: output_width(),
+        truncate,
     }
 }
 
+fn current_user(config: &Config) -> Result<String, String> {
+    config.current_user().ok_or(tr!("unknown_user"))
+}
+
+// remove the flag from the arguments, returns whether it was there
+fn take_flag(args: &[String], flag: &str) -> (Vec<String>, bool) {
+    let found = args.iter().any(|x| x == flag);
+    let args = args.iter().filter(|x| *x != flag).cloned().collect();
+    (args, found)
+}
+
+// indexes like "2 5" or ranges like "3-9"
 fn parse_args_as_indexes(max: usize, args: &[String]) -> Result<Vec<usize>, String> {
     let mut indexes = vec![];
     for arg in args {
-        let index = arg
-            .parse::<usize>()
-            .map_err(|_| format!("Invalid arguments. {}", short_help()))?;
-        if index <= 0 || index > max {
-            return Err(format!("index {} is out of range (max {})", index, max));
+        let parse = |x: &str| {
+            x.parse::<usize>()
+                .map_err(|_| tr!("invalid_arguments", help = short_help()))
+        };
+        let (first, last) = match arg.split_once('-') {
+            Some((first, last)) => (parse(first)?, parse(last)?),
+            None => (parse(arg)?, parse(arg)?),
+        };
+        for index in [first, last] {
+            if index == 0 || index > max {
+                return Err(tr!("index_out_of_range", index = index, max = max));
+            }
         }
-        indexes.push(index);
+        if first > last {
+            return Err(tr!("reversed_range", range = arg));
+        }
+        indexes.extend(first..=last);
     }
     Ok(indexes)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn edit_keeps_tracked_time() {
+        let config = Config::default();
+        let mut list = TodoList::new();
+        list.add(None, Task::new("write docs")).unwrap();
+        start(&mut list, 0, Local::now()).unwrap();
+        stop(&mut list, Local::now()).unwrap();
+
+        cmd_edit(&mut list, &config, &args("1 write the docs")).unwrap();
+        assert_eq!(list.items[0].title, "write the docs");
+        assert_eq!(list.items[0].intervals.len(), 1);
+    }
+
+    #[test]
+    fn add_out_of_range() {
+        let config = Config::default();
+        let mut list = TodoList::new();
+        cmd_add(&mut list, &config, &args("1 first")).unwrap();
+        cmd_add(&mut list, &config, &args("2 second")).unwrap();
+        assert!(cmd_add(&mut list, &config, &args("0 x")).is_err());
+        assert!(cmd_add(&mut list, &config, &args("99 x")).is_err());
+        assert_eq!(list.items.len(), 2);
+    }
+
+    #[test]
+    fn restore_snapshot() {
+        let dir = std::env::temp_dir().join(format!("todo0-test-{}", rand::random::<u64>()));
+        let file_path = dir.join("todo.json");
+        let mut list = TodoList::new();
+        list.add(None, Task::new("write docs")).unwrap();
+        let retention = Retention::default();
+        take_snapshot(&file_path, &list, None, &retention, Local::now()).unwrap();
+
+        let mut changed = list.clone();
+        cmd_add(&mut changed, &Config::default(), &args("fix login")).unwrap();
+        cmd_restore(&mut changed, &file_path, None, &args("1")).unwrap();
+        assert_eq!(changed.items, list.items);
+        assert!(cmd_restore(&mut changed, &file_path, None, &args("2")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reversed_range() {
+        assert_eq!(
+            parse_args_as_indexes(9, &args("3-5 7")),
+            Ok(vec![3, 4, 5, 7])
+        );
+        assert!(parse_args_as_indexes(9, &args("9-3")).is_err());
+    }
+
+    #[test]
+    fn markdown_keeps_due_words() {
+        let config = Config {
+            file: Some(std::path::PathBuf::from("TODO.md")),
+            ..Config::default()
+        };
+        let mut list = TodoList::new();
+        cmd_add(&mut list, &config, &args("call bob tomorrow")).unwrap();
+        assert_eq!(list.items[0].title, "call bob tomorrow");
+        assert_eq!(list.items[0].due, None);
+
+        cmd_add(&mut list, &Config::default(), &args("call bob tomorrow")).unwrap();
+        assert_eq!(list.items[1].title, "call bob");
+        assert!(list.items[1].due.is_some());
+    }
+
+    #[test]
+    fn import_keeps_tracked_time() {
+        let config = Config::default();
+        let mut list = TodoList::new();
+        list.add(None, Task::new("write docs")).unwrap();
+        start(&mut list, 0, Local::now() - Duration::minutes(5)).unwrap();
+        stop(&mut list, Local::now()).unwrap();
+        let dir = std::env::temp_dir().join(format!("todo0-test-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, content) in [
+            ("todo.ics", export_ics(&list, &config.workflow)),
+            ("todo.org", export_org(&list, &config.workflow)),
+        ] {
+            let file_path = dir.join(name);
+            std::fs::write(&file_path, content).unwrap();
+            let arg = file_path.to_string_lossy().to_string();
+            cmd_import(&mut list, &config, &[arg]).unwrap();
+            assert_eq!(list.items.len(), 1, "{}", name);
+            assert_eq!(list.items[0].intervals.len(), 1, "{}", name);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn file_config(file_path: &std::path::Path) -> Config {
+        Config {
+            file: Some(file_path.to_path_buf()),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn markdown_rejects_lost_fields() {
+        let config = file_config(std::path::Path::new("TODO.md"));
+        for line in [
+            "assign 1 bob",
+            "prio 1 high",
+            "start 1",
+            "block 1 on 2",
+            "mv-state 1 doing",
+        ] {
+            let err = check_file_command(&config, &args(line)).unwrap_err();
+            assert_eq!(err, tr!("not_in_markdown"), "{}", line);
+        }
+        assert!(check_file_command(&config, &args("check 1")).is_ok());
+        let config = file_config(std::path::Path::new("todo.json"));
+        assert!(check_file_command(&config, &args("assign 1 bob")).is_ok());
+    }
+
+    #[test]
+    fn org_rejects_lost_fields() {
+        let dir = std::env::temp_dir().join(format!("todo0-test-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file_path = dir.join("todo.org");
+        std::fs::write(&file_path, "#+TODO: TODO DOING | DONE\n* TODO write docs\n").unwrap();
+        let config = file_config(&file_path);
+        for line in [
+            "assign 1 bob",
+            "unassign 1",
+            "start 1",
+            "stop",
+            "block 1 on 2",
+        ] {
+            let err = check_file_command(&config, &args(line)).unwrap_err();
+            assert_eq!(err, tr!("not_in_org"), "{}", line);
+        }
+        assert!(check_file_command(&config, &args("mv-state 1 review")).is_err());
+        for line in ["mv-state 1 doing", "mv-state 1 done", "prio 1 high"] {
+            assert!(check_file_command(&config, &args(line)).is_ok(), "{}", line);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,101 @@
+use crate::file::get_file_path;
+use crate::hook::Hook;
+use crate::i18n::tr;
+use crate::snapshot::Retention;
+use crate::urgency::Coefficients;
+use crate::workflow::Workflow;
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path;
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct Config {
+    // the data file, defaults to file::get_file_path(). a .md file is used as
+    // a markdown checklist
+    pub file: Option<path::PathBuf>,
+    pub hooks: Vec<Hook>,
+    // derive the encryption key from this file instead of TODO0_PASSPHRASE
+    pub keyfile: Option<path::PathBuf>,
+    pub workflow: Workflow,
+    // the current user for "list --mine", overrides TODO0_USER and USER
+    pub user: Option<String>,
+    // the dir of the .wasm plugins, defaults to ~/.rustexp/todo0/plugins
+    pub plugin_dir: Option<path::PathBuf>,
+    // the weights of the urgency score of "next" and "list --sort urgency"
+    pub urgency: Coefficients,
+    // how many snapshots of the list are kept, see snapshot::Retention
+    pub snapshots: Retention,
+    // the language of the messages, e.g. "de", overrides LC_ALL, LC_MESSAGES
+    // and LANG
+    pub locale: Option<String>,
+}
+
+impl Config {
+    // TODO0_FILE overrides the config, e.g. to use the TODO.md of a repo
+    pub fn file_path(&self) -> path::PathBuf {
+        std::env::var_os("TODO0_FILE")
+            .filter(|file| !file.is_empty())
+            .map(path::PathBuf::from)
+            .or_else(|| self.file.clone())
+            .unwrap_or_else(get_file_path)
+    }
+
+    pub fn plugin_dir(&self) -> path::PathBuf {
+        self.plugin_dir.clone().unwrap_or_else(|| {
+            let mut path = dirs::home_dir().unwrap();
+            path.push(".rustexp/todo0/plugins");
+            path
+        })
+    }
+
+    pub fn current_user(&self) -> Option<String> {
+        self.user
+            .clone()
+            .or_else(|| std::env::var("TODO0_USER").ok())
+            .or_else(|| std::env::var("USER").ok())
+            .filter(|user| !user.is_empty())
+    }
+}
+
+pub fn get_config_path() -> path::PathBuf {
+    let mut path = dirs::home_dir().unwrap();
+    path.push(".rustexp/todo0/config.json");
+    path
+}
+
+// load the config file, a missing file means the default config
+pub fn load_config(file_path: &path::Path) -> Result<Config, String> {
+    let file_path_str = file_path.to_str().unwrap();
+    let content = match fs::read_to_string(file_path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+        Err(err) => {
+            return Err(tr!(
+                "read_file_failed",
+                file = format!("{:?}", file_path_str),
+                error = err
+            ))
+        }
+    };
+    if content.trim() == "" {
+        return Ok(Config::default());
+    }
+    let config: Config = serde_json::from_str(&content).map_err(|err| {
+        tr!(
+            "decode_json_failed",
+            file = format!("{:?}", file_path_str),
+            error = err
+        )
+    })?;
+    config.workflow.validate().map_err(|err| {
+        tr!(
+            "invalid_config",
+            file = format!("{:?}", file_path_str),
+            error = err
+        )
+    })?;
+    Ok(config)
+}
@@ -0,0 +1,137 @@
+use crate::i18n::tr;
+
+use argon2::Argon2;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use std::fs;
+use std::path;
+
+// encrypted files start with the magic followed by the format version
+const MAGIC: &[u8] = b"TODO0ENC";
+const FORMAT_VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = MAGIC.len() + 1 + SALT_LEN + NONCE_LEN;
+
+pub const PASSPHRASE_ENV: &str = "TODO0_PASSPHRASE";
+
+// the secret the encryption key is derived from
+pub enum Secret {
+    Passphrase(String),
+    Keyfile(Vec<u8>),
+}
+
+impl Secret {
+    // the keyfile wins over the passphrase from the environment
+    pub fn load(keyfile: Option<&path::Path>) -> Result<Self, String> {
+        if let Some(keyfile) = keyfile {
+            let content = fs::read(keyfile).map_err(|err| {
+                tr!(
+                    "read_keyfile_failed",
+                    file = format!("{:?}", keyfile),
+                    error = err
+                )
+            })?;
+            if content.is_empty() {
+                return Err(tr!("keyfile_empty", file = format!("{:?}", keyfile)));
+            }
+            return Ok(Secret::Keyfile(content));
+        }
+        match std::env::var(PASSPHRASE_ENV) {
+            Ok(passphrase) if !passphrase.is_empty() => Ok(Secret::Passphrase(passphrase)),
+            _ => Err(tr!("no_key", env = PASSPHRASE_ENV)),
+        }
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::Passphrase(passphrase) => passphrase.as_bytes(),
+            Self::Keyfile(content) => content,
+        }
+    }
+
+    fn derive_key(&self, salt: &[u8]) -> Result<XChaCha20Poly1305, String> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(self.as_bytes(), salt, &mut key)
+            .map_err(|err| tr!("derive_key_failed", error = err))?;
+        Ok(XChaCha20Poly1305::new(&key.into()))
+    }
+}
+
+pub fn is_encrypted(content: &[u8]) -> bool {
+    content.starts_with(MAGIC)
+}
+
+// whether the file exists and is encrypted
+pub fn is_encrypted_file(file_path: &path::Path) -> bool {
+    match fs::read(file_path) {
+        Ok(content) => is_encrypted(&content),
+        Err(_) => false,
+    }
+}
+
+// layout: magic | version | salt | nonce | ciphertext, the header is authenticated too
+pub fn encrypt(secret: &Secret, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let salt = rand::random::<[u8; SALT_LEN]>();
+    let nonce = rand::random::<[u8; NONCE_LEN]>();
+    let mut out = Vec::with_capacity(HEADER_LEN + plaintext.len() + 16);
+    out.extend_from_slice(MAGIC);
+    out.push(FORMAT_VERSION);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+
+    let cipher = secret.derive_key(&salt)?;
+    let payload = Payload {
+        msg: plaintext,
+        aad: &out,
+    };
+    let ciphertext = cipher
+        .encrypt(XNonce::from_slice(&nonce), payload)
+        .map_err(|_| tr!("encrypt_failed"))?;
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+pub fn decrypt(secret: &Secret, content: &[u8]) -> Result<Vec<u8>, String> {
+    if !is_encrypted(content) || content.len() < HEADER_LEN {
+        return Err(tr!("not_encrypted_file"));
+    }
+    let version = content[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(tr!("unsupported_format", format = version));
+    }
+    let (header, ciphertext) = content.split_at(HEADER_LEN);
+    let salt = &header[MAGIC.len() + 1..MAGIC.len() + 1 + SALT_LEN];
+    let nonce = &header[MAGIC.len() + 1 + SALT_LEN..];
+
+    let cipher = secret.derive_key(salt)?;
+    let payload = Payload {
+        msg: ciphertext,
+        aad: header,
+    };
+    cipher
+        .decrypt(XNonce::from_slice(nonce), payload)
+        .map_err(|_| tr!("wrong_key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_and_decrypt() {
+        let secret = Secret::Passphrase(String::from("hunter2"));
+        let content = encrypt(&secret, b"{\"items\":[]}").unwrap();
+        assert!(is_encrypted(&content));
+        assert_eq!(decrypt(&secret, &content).unwrap(), b"{\"items\":[]}");
+
+        let wrong = Secret::Keyfile(b"hunter3".to_vec());
+        assert!(decrypt(&wrong, &content).unwrap_err().contains("wrong"));
+
+        let mut tampered = content.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 1;
+        assert!(decrypt(&secret, &tampered).is_err());
+    }
+}
@@ -0,0 +1,291 @@
+use chrono::{
+    DateTime, Datelike, Duration, Local, Months, NaiveDate, NaiveTime, TimeZone, Weekday,
+};
+
+// the result of extracting a due date from the words of a task title
+#[derive(PartialEq, Debug)]
+pub struct Extracted {
+    pub title: String,
+    pub due: Option<DateTime<Local>>,
+    // the words which were interpreted as the due date
+    pub phrase: String,
+}
+
+enum Part {
+    Date(NaiveDate),
+    Time(NaiveTime),
+    DateTime(NaiveDate, NaiveTime),
+}
+
+// words which may come before a date and are removed together with it
+const CONNECTORS: [&str; 4] = ["on", "at", "by", "due"];
+
+// find phrases like "tomorrow", "next fri", "in 3 days", "end of month" or
+// "9am" in the words, and remove them from the title. only the first date and
+// the first time are taken, later ones are kept in the title. a time without a
+// date is today, or tomorrow if it has already passed.
+pub fn extract_due(words: &[String], now: DateTime<Local>) -> Extracted {
+    let today = now.date_naive();
+    let lower: Vec<String> = words
+        .iter()
+        .map(|x| x.to_lowercase().trim_end_matches(',').to_string())
+        .collect();
+
+    let mut date = None;
+    let mut time = None;
+    let mut title = vec![];
+    let mut phrase = vec![];
+    let mut i = 0;
+    while i < words.len() {
+        let skip = if CONNECTORS.contains(&lower[i].as_str()) {
+            1
+        } else {
+            0
+        };
+        let mut matched = match_part(&lower[i + skip..], today, now);
+        // a bare weekday is only a date after a connector, e.g. "call bob on fri"
+        if matched.is_none() && skip == 1 {
+            matched = lower
+                .get(i + 1)
+                .and_then(|day| parse_weekday(day))
+                .map(|weekday| (1, Part::Date(next_weekday(today, weekday))));
+        }
+        let accepted = match &matched {
+            Some((_, Part::Date(_))) => date.is_none(),
+            Some((_, Part::Time(_))) => time.is_none(),
+            Some((_, Part::DateTime(_, _))) => date.is_none() && time.is_none(),
+            None => false,
+        };
+        match matched {
+            Some((len, part)) if accepted => {
+                match part {
+                    Part::Date(d) => date = Some(d),
+                    Part::Time(t) => time = Some(t),
+                    Part::DateTime(d, t) => {
+                        date = Some(d);
+                        time = Some(t);
+                    }
+                }
+                phrase.extend_from_slice(&words[i..i + skip + len]);
+                i += skip + len;
+            }
+            _ => {
+                title.push(words[i].clone());
+                i += 1;
+            }
+        }
+    }
+
+    let due = match (date, time) {
+        (None, None) => None,
+        (Some(date), time) => to_local(date, time.unwrap_or(NaiveTime::MIN)),
+        (None, Some(time)) => {
+            let date = if time <= now.time() {
+                today + Duration::days(1)
+            } else {
+                today
+            };
+            to_local(date, time)
+        }
+    };
+    Extracted {
+        title: title.join(" "),
+        due,
+        phrase: phrase.join(" "),
+    }
+}
+
+// parse the whole text as a date, e.g. for "report --since yesterday"
+pub fn parse_date(text: &str, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let words: Vec<String> = text.split_whitespace().map(String::from).collect();
+    let extracted = extract_due(&words, now);
+    match extracted.title.is_empty() {
+        true => extracted.due,
+        false => None,
+    }
+}
+
+pub fn format_due(due: DateTime<Local>) -> String {
+    if due.time() == NaiveTime::MIN {
+        due.format("%a %Y-%m-%d").to_string()
+    } else {
+        due.format("%a %Y-%m-%d %H:%M").to_string()
+    }
+}
+
+fn to_local(date: NaiveDate, time: NaiveTime) -> Option<DateTime<Local>> {
+    Local.from_local_datetime(&date.and_time(time)).earliest()
+}
+
+// try to match a date or time phrase at the start of the words, returns the
+// number of words it takes
+fn match_part(words: &[String], today: NaiveDate, now: DateTime<Local>) -> Option<(usize, Part)> {
+    let word = |i: usize| words.get(i).map(|x| x.as_str()).unwrap_or("");
+    match (word(0), word(1), word(2)) {
+        ("today", _, _) => Some((1, Part::Date(today))),
+        ("tomorrow", _, _) | ("tmr", _, _) => Some((1, Part::Date(today + Duration::days(1)))),
+        ("yesterday", _, _) => Some((1, Part::Date(today - Duration::days(1)))),
+        ("tonight", _, _) => Some((1, Part::DateTime(today, NaiveTime::from_hms_opt(20, 0, 0)?))),
+        ("noon", _, _) => Some((1, Part::Time(NaiveTime::from_hms_opt(12, 0, 0)?))),
+        ("next", "week", _) => Some((2, Part::Date(today + Duration::weeks(1)))),
+        ("next", "month", _) => Some((2, Part::Date(today.checked_add_months(Months::new(1))?))),
+        ("next", day, _) => {
+            let weekday = parse_weekday(day)?;
+            Some((2, Part::Date(next_weekday(today, weekday))))
+        }
+        ("end", "of", "week") | ("eow", _, _) => {
+            let len = if word(0) == "eow" { 1 } else { 3 };
+            let days = 6 - today.weekday().num_days_from_monday() as i64;
+            Some((len, Part::Date(today + Duration::days(days))))
+        }
+        ("end", "of", "month") | ("eom", _, _) => {
+            let len = if word(0) == "eom" { 1 } else { 3 };
+            let first = today.with_day(1)?.checked_add_months(Months::new(1))?;
+            Some((len, Part::Date(first - Duration::days(1))))
+        }
+        ("in", amount, unit) => {
+            let amount: i64 = match amount {
+                "a" | "an" | "one" => 1,
+                _ => amount.parse().ok()?,
+            };
+            let unit = unit.trim_end_matches('s');
+            // an amount out of the range of dates is not a date, e.g. "in 99999999 days"
+            let part = match unit {
+                "minute" | "min" => {
+                    let at = now.checked_add_signed(Duration::try_minutes(amount)?)?;
+                    Part::DateTime(at.date_naive(), at.time())
+                }
+                "hour" => {
+                    let at = now.checked_add_signed(Duration::try_hours(amount)?)?;
+                    Part::DateTime(at.date_naive(), at.time())
+                }
+                "day" => Part::Date(today.checked_add_signed(Duration::try_days(amount)?)?),
+                "week" => Part::Date(today.checked_add_signed(Duration::try_weeks(amount)?)?),
+                "month" => {
+                    let months = Months::new(u32::try_from(amount).ok()?);
+                    Part::Date(today.checked_add_months(months)?)
+                }
+                _ => return None,
+            };
+            Some((3, part))
+        }
+        (day, _, _) => {
+            if let Ok(date) = NaiveDate::parse_from_str(day, "%Y-%m-%d") {
+                return Some((1, Part::Date(date)));
+            }
+            if let Some(time) = parse_time(day) {
+                return Some((1, Part::Time(time)));
+            }
+            None
+        }
+    }
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tues" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thur" | "thurs" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+// the first day with the weekday after today
+fn next_weekday(today: NaiveDate, weekday: Weekday) -> NaiveDate {
+    let from = today.weekday().num_days_from_monday() as i64;
+    let to = weekday.num_days_from_monday() as i64;
+    let days = (to - from + 6).rem_euclid(7) + 1;
+    today + Duration::days(days)
+}
+
+// "9am", "9:30pm", "12am", "14:00"
+fn parse_time(word: &str) -> Option<NaiveTime> {
+    let (clock, offset) = if let Some(clock) = word.strip_suffix("am") {
+        (clock, Some(0))
+    } else if let Some(clock) = word.strip_suffix("pm") {
+        (clock, Some(12))
+    } else {
+        (word, None)
+    };
+    let (hour, min) = match clock.split_once(':') {
+        Some((hour, min)) if min.len() == 2 => (hour.parse::<u32>().ok()?, min.parse().ok()?),
+        None if offset.is_some() => (clock.parse::<u32>().ok()?, 0),
+        _ => return None,
+    };
+    let hour = match offset {
+        None => hour,
+        Some(_) if hour == 0 || hour > 12 => return None,
+        Some(offset) => hour % 12 + offset,
+    };
+    NaiveTime::from_hms_opt(hour, min, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Wed 2024-03-06 10:00
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 6, 10, 0, 0).unwrap()
+    }
+
+    fn extract(text: &str) -> (String, Option<String>) {
+        let words: Vec<String> = text.split_whitespace().map(String::from).collect();
+        let res = extract_due(&words, now());
+        (
+            res.title,
+            res.due.map(|x| x.format("%Y-%m-%d %H:%M").to_string()),
+        )
+    }
+
+    #[test]
+    fn extract_phrases() {
+        let cases = [
+            ("call bob tomorrow", "call bob", Some("2024-03-07 00:00")),
+            (
+                "call bob next fri 9am",
+                "call bob",
+                Some("2024-03-08 09:00"),
+            ),
+            ("call bob on wed", "call bob", Some("2024-03-13 00:00")),
+            (
+                "pay rent by end of month",
+                "pay rent",
+                Some("2024-03-31 00:00"),
+            ),
+            ("review in 3 days", "review", Some("2024-03-09 00:00")),
+            ("review in 2 hours", "review", Some("2024-03-06 12:00")),
+            ("standup 9:30am", "standup", Some("2024-03-07 09:30")),
+            ("lunch at noon", "lunch", Some("2024-03-06 12:00")),
+            ("ship 2024-04-01 5pm", "ship", Some("2024-04-01 17:00")),
+            ("Friday party", "Friday party", None),
+            ("read chapter 9", "read chapter 9", None),
+            ("fix in 99999999 days", "fix in 99999999 days", None),
+            (
+                "fix in 9223372036854775807 mins",
+                "fix in 9223372036854775807 mins",
+                None,
+            ),
+            ("fix in -1 months", "fix in -1 months", None),
+            (
+                "today and tomorrow",
+                "and tomorrow",
+                Some("2024-03-06 00:00"),
+            ),
+        ];
+        for (input, title, due) in cases {
+            let due = due.map(String::from);
+            assert_eq!(extract(input), (title.to_string(), due), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_whole_date() {
+        assert!(parse_date("yesterday", now()).is_some());
+        assert!(parse_date("yesterday lunch", now()).is_none());
+    }
+}
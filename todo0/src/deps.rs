@@ -0,0 +1,156 @@
+use crate::i18n::tr;
+use crate::todo::*;
+
+use std::fmt::Write;
+
+// the blockers of the task which are not done yet, deleted blockers are ignored
+pub fn open_blockers<'a>(list: &'a TodoList, task: &Task) -> Vec<&'a Task> {
+    list.items
+        .iter()
+        .filter(|x| task.blocked_by.contains(&x.id) && !x.done)
+        .collect()
+}
+
+pub fn is_blocked(list: &TodoList, task: &Task) -> bool {
+    !open_blockers(list, task).is_empty()
+}
+
+// tasks which are not done and not blocked
+pub fn is_ready(list: &TodoList, task: &Task) -> bool {
+    !task.done && !is_blocked(list, task)
+}
+
+// mark the task at index as blocked by the task at on_index
+pub fn block(list: &mut TodoList, index: usize, on_index: usize) -> Result<ActionResult, Error> {
+    let task = list.items.get(index).ok_or(Error::NotFound)?;
+    let blocker = list.items.get(on_index).ok_or(Error::NotFound)?;
+    if task.id == blocker.id {
+        return Err(Error::Validation(tr!("block_itself")));
+    }
+    if task.blocked_by.contains(&blocker.id) {
+        return Ok(ActionResult::Updated(task.id));
+    }
+    if depends_on(list, blocker.id, task.id) {
+        return Err(Error::Validation(tr!(
+            "block_cycle",
+            blocker = blocker.title,
+            task = task.title
+        )));
+    }
+
+    let mut task = task.clone();
+    task.blocked_by.push(blocker.id);
+    list.edit(Some(Position::ById(task.id)), task)
+}
+
+// remove the blocker at on_index from the task at index, or all blockers
+pub fn unblock(
+    list: &mut TodoList,
+    index: usize,
+    on_index: Option<usize>,
+) -> Result<ActionResult, Error> {
+    let mut task = list.items.get(index).ok_or(Error::NotFound)?.clone();
+    match on_index {
+        None => task.blocked_by.clear(),
+        Some(on_index) => {
+            let blocker = list.items.get(on_index).ok_or(Error::NotFound)?;
+            task.blocked_by.retain(|id| *id != blocker.id);
+        }
+    }
+    list.edit(Some(Position::ById(task.id)), task)
+}
+
+// whether the task with id depends on target, directly or through other tasks
+fn depends_on(list: &TodoList, id: TaskId, target: TaskId) -> bool {
+    let mut visited: Vec<TaskId> = vec![];
+    let mut stack = vec![id];
+    while let Some(id) = stack.pop() {
+        if id == target {
+            return true;
+        }
+        if visited.contains(&id) {
+            continue;
+        }
+        visited.push(id);
+        if let Some(task) = list.items.iter().find(|x| x.id == id) {
+            stack.extend(task.blocked_by.iter());
+        }
+    }
+    false
+}
+
+// render the dependencies as a graphviz dot graph, edges go from the blocker
+// to the blocked task
+pub fn format_dot(list: &TodoList) -> String {
+    let mut buf = String::new();
+    buf.write_str("digraph todo0 {\n").unwrap();
+    buf.write_str("  node [shape=box];\n").unwrap();
+    for (idx, task) in list.items.iter().enumerate() {
+        let label = format!("{}. {}", idx + 1, task.title);
+        let style = if task.done {
+            ", style=dashed"
+        } else if is_blocked(list, task) {
+            ", style=filled, fillcolor=lightgray"
+        } else {
+            ""
+        };
+        writeln!(
+            buf,
+            "  t{} [label=\"{}\"{}];",
+            task.id.0,
+            label.replace('\\', "\\\\").replace('"', "\\\""),
+            style
+        )
+        .unwrap();
+    }
+    for task in list.items.iter() {
+        for blocker in list
+            .items
+            .iter()
+            .filter(|x| task.blocked_by.contains(&x.id))
+        {
+            writeln!(buf, "  t{} -> t{};", blocker.id.0, task.id.0).unwrap();
+        }
+    }
+    buf.write_str("}").unwrap();
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn block_tasks() {
+        let mut list = TodoList::new();
+        list.add(None, Task::new("A")).unwrap();
+        list.add(None, Task::new("B")).unwrap();
+        list.add(None, Task::new("C")).unwrap();
+
+        // C is blocked by B, B is blocked by A
+        block(&mut list, 2, 1).unwrap();
+        block(&mut list, 1, 0).unwrap();
+        let ready: Vec<_> = list
+            .items
+            .iter()
+            .filter(|x| is_ready(&list, x))
+            .map(|x| x.title.as_str())
+            .collect();
+        assert_eq!(ready, vec!["A"]);
+
+        // A -> B -> C -> A
+        assert!(block(&mut list, 0, 2).is_err());
+        assert!(block(&mut list, 0, 0).is_err());
+
+        list.items[0].done = true;
+        assert!(is_ready(&list, &list.items[1]));
+        assert!(!is_ready(&list, &list.items[2]));
+
+        unblock(&mut list, 2, None).unwrap();
+        assert!(is_ready(&list, &list.items[2]));
+        assert!(format_dot(&list).contains(&format!(
+            "t{} -> t{};",
+            list.items[0].id.0, list.items[1].id.0
+        )));
+    }
+}
@@ -1,5 +1,11 @@
+use crate::crypt::*;
+use crate::i18n::tr;
+use crate::markdown::*;
+use crate::migrate::*;
+use crate::org::*;
 use crate::TodoList;
 
+use serde::Serialize;
 use serde_json;
 use std::fs;
 use std::io;
@@ -13,50 +19,369 @@ pub fn get_file_path() -> path::PathBuf {
     path
 }
 
-pub fn load_from_file(file_path: &path::Path) -> Result<TodoList, String> {
-    let file_path_str = file_path.to_str().unwrap();
+// load the list, an encrypted file requires the secret to decrypt it
+pub fn load_from_file(file_path: &path::Path, secret: Option<&Secret>) -> Result<TodoList, String> {
+    // the quoted path for the messages
+    let file_path_str = format!("{:?}", file_path.to_str().unwrap());
+    if is_document_file(file_path) {
+        return load_document_file(file_path);
+    }
     let mut file = match fs::File::open(&file_path) {
         Ok(file) => Ok(file),
         Err(err) => match err.kind() {
             io::ErrorKind::NotFound => {
                 let dir_path = file_path.parent().unwrap();
-                fs::create_dir_all(dir_path)
-                    .map_err(|err| format!("failed to create dir {:?}: {}", dir_path, err))?;
+                fs::create_dir_all(dir_path).map_err(|err| {
+                    tr!(
+                        "create_dir_failed",
+                        dir = format!("{:?}", dir_path),
+                        error = err
+                    )
+                })?;
                 fs::File::create(file_path)
-                    .map_err(|err| format!("failed to create file {:?}: {}", file_path_str, err))
+                    .map_err(|err| tr!("create_file_failed", file = file_path_str, error = err))?;
+                return Ok(TodoList::new());
             }
-            _ => Err(format!("failed to open file {:?}: {}", file_path_str, err)),
+            _ => Err(tr!("open_file_failed", file = file_path_str, error = err)),
         },
     }?;
 
-    let mut content = String::new();
-    file.read_to_string(&mut content)
-        .map_err(|err| format!("failed to read file {:?}: {}", file_path_str, err))?;
+    let mut content = vec![];
+    file.read_to_end(&mut content)
+        .map_err(|err| tr!("read_file_failed", file = file_path_str, error = err))?;
+    if is_encrypted(&content) {
+        let secret = secret.ok_or(tr!(
+            "file_encrypted_no_key",
+            file = file_path_str,
+            env = PASSPHRASE_ENV
+        ))?;
+        content = decrypt(secret, &content)
+            .map_err(|err| tr!("decrypt_file_failed", file = file_path_str, error = err))?;
+    }
+    let content = String::from_utf8(content)
+        .map_err(|err| tr!("read_file_failed", file = file_path_str, error = err))?;
 
     if content.trim() == "" {
         return Ok(TodoList::new());
     }
 
-    let todo_list: TodoList = serde_json::from_str(&content).map_err(|err| {
-        format!(
-            "failed to decode json from file {:?}: {}",
-            file_path_str, err
+    let doc: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|err| tr!("decode_json_failed", file = file_path_str, error = err))?;
+    let version = get_version(&doc)?;
+    if version < CURRENT_VERSION {
+        backup_file(file_path, version)?;
+    }
+    let doc = migrate(doc).map_err(|err| tr!("file_error", file = file_path_str, error = err))?;
+
+    let todo_list: TodoList = serde_json::from_value(doc)
+        .map_err(|err| tr!("decode_json_failed", file = file_path_str, error = err))?;
+    Ok(todo_list)
+}
+
+// a markdown or org file, which is edited in place instead of being rewritten
+pub fn is_document_file(file_path: &path::Path) -> bool {
+    is_markdown_file(file_path) || is_org_file(file_path)
+}
+
+// a markdown file is read as a checklist and an org file as headings, the
+// file is not created until it is saved
+fn load_document_file(file_path: &path::Path) -> Result<TodoList, String> {
+    let load = match is_org_file(file_path) {
+        true => load_org,
+        false => load_markdown,
+    };
+    match fs::read_to_string(file_path) {
+        Ok(content) => Ok(load(&content)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(TodoList::new()),
+        Err(err) => Err(tr!(
+            "read_file_failed",
+            file = format!("{:?}", file_path),
+            error = err
+        )),
+    }
+}
+
+// keep a copy of the file before migrating it, e.g. todo.json -> todo.json.v0.bak
+fn backup_file(file_path: &path::Path, version: u64) -> Result<(), String> {
+    let mut backup_path = file_path.as_os_str().to_owned();
+    backup_path.push(format!(".v{}.bak", version));
+    let backup_path = path::PathBuf::from(backup_path);
+    if backup_path.exists() {
+        return Ok(());
+    }
+    fs::copy(file_path, &backup_path).map_err(|err| {
+        tr!(
+            "backup_failed",
+            file = format!("{:?}", backup_path),
+            error = err
         )
     })?;
-    Ok(todo_list)
+    Ok(())
+}
+
+// the backups of the file, e.g. todo.json.v0.bak
+fn list_backups(file_path: &path::Path) -> Result<Vec<path::PathBuf>, String> {
+    let dir = file_path.parent().unwrap();
+    let prefix = format!("{}.v", file_path.file_name().unwrap().to_string_lossy());
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => {
+            return Err(tr!(
+                "read_dir_failed",
+                dir = format!("{:?}", dir),
+                error = err
+            ))
+        }
+    };
+    let backups = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.strip_prefix(&prefix)
+                .and_then(|x| x.strip_suffix(".bak"))
+                .is_some_and(|x| !x.is_empty() && x.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(|entry| entry.path())
+        .collect();
+    Ok(backups)
+}
+
+// write the backups again with the new secret, when the file is encrypted or
+// decrypted. they keep the content of their version, so only the bytes are
+// encrypted, not the list.
+pub fn reencrypt_backups(
+    file_path: &path::Path,
+    from: Option<&Secret>,
+    to: Option<&Secret>,
+) -> Result<(), String> {
+    for path in list_backups(file_path)? {
+        let mut content = fs::read(&path).map_err(|err| {
+            tr!(
+                "read_file_failed",
+                file = format!("{:?}", path),
+                error = err
+            )
+        })?;
+        if is_encrypted(&content) {
+            // a backup may already be written with the new secret
+            let secret = from
+                .or(to)
+                .ok_or(tr!("file_encrypted", file = format!("{:?}", path)))?;
+            content = decrypt(secret, &content).map_err(|err| {
+                tr!(
+                    "decrypt_file_failed",
+                    file = format!("{:?}", path),
+                    error = err
+                )
+            })?;
+        }
+        if let Some(secret) = to {
+            content = encrypt(secret, &content)?;
+        }
+        write_file(&path, &content)?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    version: u64,
+    #[serde(flatten)]
+    todo_list: &'a TodoList,
 }
 
-pub fn save_to_file(file_path: &path::Path, todo_list: TodoList) -> Result<(), String> {
+// save the list, it is encrypted when a secret is given
+pub fn save_to_file(
+    file_path: &path::Path,
+    todo_list: TodoList,
+    secret: Option<&Secret>,
+) -> Result<(), String> {
     let file_path_str = file_path.to_str().unwrap();
+    if is_document_file(file_path) {
+        if secret.is_some() {
+            return Err(tr!(
+                "file_not_encryptable",
+                file = format!("{:?}", file_path_str)
+            ));
+        }
+        return save_document_file(file_path, &todo_list);
+    }
 
-    let todo_json = serde_json::to_string(&todo_list)
-        .map_err(|err| format!("failed to encode json: {}", err))?;
+    let envelope = Envelope {
+        version: CURRENT_VERSION,
+        todo_list: &todo_list,
+    };
+    let todo_json =
+        serde_json::to_string(&envelope).map_err(|err| tr!("encode_json_failed", error = err))?;
+    let content = match secret {
+        None => todo_json.into_bytes(),
+        Some(secret) => encrypt(secret, todo_json.as_bytes())?,
+    };
 
-    let mut file = fs::File::create(file_path_str)
-        .map_err(|err| format!("failed to write to file {:?}: {}", file_path_str, err))?;
+    write_file(file_path, &content)
+}
 
-    file.set_len(0).unwrap(); // truncate
-    file.write_all(&todo_json.as_bytes())
-        .map_err(|err| format!("failed to write to file {:?}: {}", file_path_str, err))?;
+// write a temp file next to the file and rename it over the file, so that
+// other processes read either the old or the new content, never a part of it
+pub fn write_file(file_path: &path::Path, content: &[u8]) -> Result<(), String> {
+    let mut tmp_name = file_path.file_name().unwrap_or_default().to_owned();
+    tmp_name.push(format!(".tmp{}", std::process::id()));
+    let tmp_path = file_path.with_file_name(tmp_name);
+    let res = fs::File::create(&tmp_path)
+        .and_then(|mut file| file.write_all(content).and_then(|_| file.sync_all()))
+        .and_then(|_| fs::rename(&tmp_path, file_path));
+    if let Err(err) = res {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(tr!(
+            "write_file_failed",
+            file = format!("{:?}", file_path),
+            error = err
+        ));
+    }
     Ok(())
 }
+
+// write only the changed tasks back, the rest of the document is kept
+fn save_document_file(file_path: &path::Path, todo_list: &TodoList) -> Result<(), String> {
+    let content = match fs::read_to_string(file_path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => {
+            return Err(tr!(
+                "read_file_failed",
+                file = format!("{:?}", file_path),
+                error = err
+            ))
+        }
+    };
+    let new_content = match is_org_file(file_path) {
+        true => save_org(&content, todo_list),
+        false => save_markdown(&content, todo_list),
+    };
+    if new_content == content && file_path.exists() {
+        return Ok(());
+    }
+    write_file(file_path, new_content.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> path::PathBuf {
+        path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata")
+            .join(name)
+    }
+
+    // copy the fixture into a fresh temp dir, so backups do not touch testdata
+    fn copy_fixture(name: &str) -> path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("todo0-test-{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        let file_path = dir.join("todo.json");
+        fs::copy(fixture(name), &file_path).unwrap();
+        file_path
+    }
+
+    fn get_tasks(list: &TodoList) -> Vec<(&str, bool)> {
+        list.items
+            .iter()
+            .map(|x| (x.title.as_str(), x.done))
+            .collect()
+    }
+
+    #[test]
+    fn load_every_version() {
+        for version in 0..=CURRENT_VERSION {
+            let file_path = copy_fixture(&format!("v{}.json", version));
+            let list = load_from_file(&file_path, None).unwrap();
+            assert_eq!(
+                get_tasks(&list),
+                vec![("hello foo", false), ("foo bar", true)],
+                "version {}",
+                version
+            );
+
+            // only older versions are backed up
+            let backup = file_path.with_file_name(format!("todo.json.v{}.bak", version));
+            assert_eq!(backup.exists(), version < CURRENT_VERSION);
+            fs::remove_dir_all(file_path.parent().unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn migrate_done_to_state() {
+        let file_path = copy_fixture("v4.json");
+        let list = load_from_file(&file_path, None).unwrap();
+        let states: Vec<_> = list.items.iter().map(|x| x.state.as_str()).collect();
+        assert_eq!(states, vec!["todo", "done"]);
+        fs::remove_dir_all(file_path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn save_current_version() {
+        let file_path = copy_fixture("v0.json");
+        let list = load_from_file(&file_path, None).unwrap();
+        save_to_file(&file_path, list, None).unwrap();
+
+        let doc: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&file_path).unwrap()).unwrap();
+        assert_eq!(get_version(&doc), Ok(CURRENT_VERSION));
+        let list = load_from_file(&file_path, None).unwrap();
+        assert_eq!(list.items.len(), 2);
+        fs::remove_dir_all(file_path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn save_encrypted() {
+        let file_path = copy_fixture("v0.json");
+        let list = load_from_file(&file_path, None).unwrap();
+        let secret = Secret::Passphrase(String::from("hunter2"));
+        save_to_file(&file_path, list, Some(&secret)).unwrap();
+        assert!(is_encrypted_file(&file_path));
+
+        let list = load_from_file(&file_path, Some(&secret)).unwrap();
+        assert_eq!(list.items.len(), 2);
+        assert!(load_from_file(&file_path, None).is_err());
+        let wrong = Secret::Passphrase(String::from("hunter3"));
+        assert!(load_from_file(&file_path, Some(&wrong))
+            .unwrap_err()
+            .contains("wrong passphrase"));
+        fs::remove_dir_all(file_path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn encrypt_backups() {
+        let file_path = copy_fixture("v0.json");
+        load_from_file(&file_path, None).unwrap();
+        let backup = file_path.with_file_name("todo.json.v0.bak");
+        let plaintext = fs::read(&backup).unwrap();
+        let secret = Secret::Passphrase(String::from("hunter2"));
+
+        reencrypt_backups(&file_path, None, Some(&secret)).unwrap();
+        assert!(is_encrypted_file(&backup));
+        // running it again does not encrypt twice
+        reencrypt_backups(&file_path, None, Some(&secret)).unwrap();
+        reencrypt_backups(&file_path, Some(&secret), None).unwrap();
+        assert_eq!(fs::read(&backup).unwrap(), plaintext);
+        fs::remove_dir_all(file_path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn write_replaces_file() {
+        let file_path = copy_fixture("v0.json");
+        let dir = file_path.parent().unwrap();
+        write_file(&file_path, b"{}").unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "{}");
+        // only the file is left, not the temp file
+        assert_eq!(fs::read_dir(dir).unwrap().count(), 1);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reject_newer_version() {
+        let doc = serde_json::json!({ "version": CURRENT_VERSION + 1, "items": [] });
+        assert!(migrate(doc).unwrap_err().contains("newer"));
+    }
+}
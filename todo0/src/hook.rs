@@ -0,0 +1,227 @@
+use crate::i18n::tr;
+use crate::todo::Task;
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum HookStage {
+    Pre,
+    Post,
+}
+
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum HookEvent {
+    Add,
+    Edit,
+    Check,
+    Uncheck,
+    Move,
+    Del,
+}
+
+// a shell command to run before or after a task changes, e.g.
+//   { "stage": "pre", "event": "del", "command": "./confirm.sh" }
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Hook {
+    pub stage: HookStage,
+    pub event: HookEvent,
+    pub command: String,
+}
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    stage: HookStage,
+    event: HookEvent,
+    task: &'a Task,
+}
+
+impl HookStage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Pre => "pre",
+            Self::Post => "post",
+        }
+    }
+}
+
+impl HookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Edit => "edit",
+            Self::Check => "check",
+            Self::Uncheck => "uncheck",
+            Self::Move => "move",
+            Self::Del => "del",
+        }
+    }
+}
+
+thread_local! {
+    // the post hooks held back by defer_post_hooks, with their tasks
+    static DEFERRED: RefCell<Option<Vec<(Hook, Task)>>> = const { RefCell::new(None) };
+}
+
+// hold back the post hooks until run_deferred_hooks, i.e. until the change is
+// saved, so that they do not report changes which are thrown away
+pub fn defer_post_hooks() {
+    DEFERRED.with(|x| *x.borrow_mut() = Some(vec![]));
+}
+
+// run the held back post hooks, nothing when they are not deferred
+pub fn run_deferred_hooks() {
+    let deferred = DEFERRED.with(|x| x.borrow_mut().take()).unwrap_or_default();
+    for (hook, task) in deferred {
+        if let Err(err) = run_hook(&hook, &task) {
+            eprintln!("{}", tr!("warning", error = err));
+        }
+    }
+}
+
+// forget the held back post hooks, the changes were not saved
+pub fn discard_deferred_hooks() {
+    DEFERRED.with(|x| *x.borrow_mut() = None);
+}
+
+// run all hooks matching the stage and event, passing the task as json on stdin.
+// a pre hook exiting with non-zero status vetoes the change, a failing post hook
+// only prints a warning because the change is already made.
+pub fn run_hooks(
+    hooks: &[Hook],
+    stage: HookStage,
+    event: HookEvent,
+    task: &Task,
+) -> Result<(), String> {
+    let matched = hooks
+        .iter()
+        .filter(|hook| hook.stage == stage && hook.event == event);
+    for hook in matched {
+        if stage == HookStage::Post {
+            let deferred = DEFERRED.with(|x| match x.borrow_mut().as_mut() {
+                Some(deferred) => {
+                    deferred.push((hook.clone(), task.clone()));
+                    true
+                }
+                None => false,
+            });
+            if deferred {
+                continue;
+            }
+        }
+        let res = run_hook(hook, task);
+        match (stage, res) {
+            (_, Ok(())) => {}
+            (HookStage::Pre, Err(err)) => return Err(err),
+            (HookStage::Post, Err(err)) => eprintln!("{}", tr!("warning", error = err)),
+        }
+    }
+    Ok(())
+}
+
+fn run_hook(hook: &Hook, task: &Task) -> Result<(), String> {
+    let name = format!("{}-{}", hook.stage.name(), hook.event.name());
+    let payload = Payload {
+        stage: hook.stage,
+        event: hook.event,
+        task,
+    };
+    let payload =
+        serde_json::to_string(&payload).map_err(|err| tr!("encode_json_failed", error = err))?;
+
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(&hook.command)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|err| {
+            tr!(
+                "run_hook_failed",
+                event = name,
+                hook = format!("{:?}", hook.command),
+                error = err
+            )
+        })?;
+
+    // the hook may exit without reading stdin, so ignore broken pipes
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+    let status = child.wait().map_err(|err| {
+        tr!(
+            "run_hook_failed",
+            event = name,
+            hook = format!("{:?}", hook.command),
+            error = err
+        )
+    })?;
+    if !status.success() {
+        return Err(tr!(
+            "hook_rejected",
+            event = name,
+            hook = format!("{:?}", hook.command),
+            title = task.title,
+            status = status
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(stage: HookStage, command: &str) -> Hook {
+        Hook {
+            stage,
+            event: HookEvent::Add,
+            command: command.to_string(),
+        }
+    }
+
+    #[test]
+    fn pre_hook_vetoes() {
+        let task = Task::new("A");
+        let hooks = vec![
+            hook(HookStage::Pre, "cat > /dev/null"),
+            hook(HookStage::Pre, "exit 3"),
+        ];
+        let res = run_hooks(&hooks, HookStage::Pre, HookEvent::Add, &task);
+        assert!(res.unwrap_err().contains("pre-add hook"));
+
+        // hooks for other events are not run
+        let res = run_hooks(&hooks, HookStage::Pre, HookEvent::Del, &task);
+        assert_eq!(res, Ok(()));
+
+        // failing post hooks do not undo the change
+        let hooks = vec![hook(HookStage::Post, "exit 1")];
+        let res = run_hooks(&hooks, HookStage::Post, HookEvent::Add, &task);
+        assert_eq!(res, Ok(()));
+    }
+
+    #[test]
+    fn deferred_post_hooks() {
+        let dir = std::env::temp_dir().join(format!("todo0-test-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let out = dir.join("out");
+        let hooks = vec![hook(HookStage::Post, &format!("cat >> {:?}", out))];
+        let task = Task::new("A");
+
+        defer_post_hooks();
+        run_hooks(&hooks, HookStage::Post, HookEvent::Add, &task).unwrap();
+        assert!(!out.exists());
+        discard_deferred_hooks();
+        run_deferred_hooks();
+        assert!(!out.exists());
+
+        defer_post_hooks();
+        run_hooks(&hooks, HookStage::Post, HookEvent::Add, &task).unwrap();
+        run_deferred_hooks();
+        assert!(std::fs::read_to_string(&out).unwrap().contains("\"A\""));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,349 @@
+// the messages of each locale are in src/i18n/<locale>.rs as (key, message)
+// pairs. a message with a count has one key per plural category of the
+// locale, e.g. "tasks_added.one" and "tasks_added.other", and {name} in a
+// message is replaced by the argument of that name.
+mod de;
+mod en;
+
+use std::sync::OnceLock;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Locale {
+    En,
+    De,
+}
+
+pub const LOCALES: [Locale; 2] = [Locale::En, Locale::De];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Plural {
+    One,
+    Other,
+}
+
+impl Plural {
+    fn suffix(self) -> &'static str {
+        match self {
+            Plural::One => "one",
+            Plural::Other => "other",
+        }
+    }
+}
+
+impl Locale {
+    fn name(self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::De => "de",
+        }
+    }
+
+    // "de", "de_AT.UTF-8" or "de-AT", other languages are not known
+    pub fn parse(name: &str) -> Option<Locale> {
+        let lang = name.split(['_', '-', '.', '@']).next().unwrap_or("");
+        LOCALES
+            .into_iter()
+            .find(|x| x.name().eq_ignore_ascii_case(lang))
+    }
+
+    fn messages(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Locale::En => en::MESSAGES,
+            Locale::De => de::MESSAGES,
+        }
+    }
+
+    // the plural category of the count, see the CLDR plural rules
+    pub fn plural(self, count: u64) -> Plural {
+        match self {
+            Locale::En | Locale::De if count == 1 => Plural::One,
+            Locale::En | Locale::De => Plural::Other,
+        }
+    }
+
+    fn lookup(self, key: &str) -> Option<&'static str> {
+        self.messages()
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, message)| *message)
+    }
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+// the "locale" of the config wins over LC_ALL, LC_MESSAGES and LANG, english
+// is used when none of them is a known locale
+pub fn init(config_locale: Option<&str>) {
+    let from_env = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|x| !x.is_empty());
+    let locale = config_locale
+        .map(String::from)
+        .or(from_env)
+        .and_then(|x| Locale::parse(&x))
+        .unwrap_or(Locale::En);
+    let _ = LOCALE.set(locale);
+}
+
+// english until init is called, e.g. in the tests
+pub fn locale() -> Locale {
+    LOCALE.get().copied().unwrap_or(Locale::En)
+}
+
+// the message of the key in the current locale, or in english when it is not
+// translated. a "count" argument selects the plural form.
+pub fn translate(key: &str, args: &[(&str, String)]) -> String {
+    translate_to(locale(), key, args)
+}
+
+fn translate_to(locale: Locale, key: &str, args: &[(&str, String)]) -> String {
+    let count = args
+        .iter()
+        .find(|(name, _)| *name == "count")
+        .and_then(|(_, value)| value.parse::<u64>().ok());
+    let lookup = |locale: Locale| match count {
+        Some(count) => {
+            let key = format!("{}.{}", key, locale.plural(count).suffix());
+            locale.lookup(&key)
+        }
+        None => locale.lookup(key),
+    };
+    match lookup(locale).or_else(|| lookup(Locale::En)) {
+        Some(message) => fill(message, args),
+        None => key.to_string(),
+    }
+}
+
+// replace {name} by the argument, other braces are kept as they are
+fn fill(message: &str, args: &[(&str, String)]) -> String {
+    let mut out = String::new();
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest[1..].find('}').and_then(|end| {
+            let name = &rest[1..end + 1];
+            args.iter()
+                .find(|(x, _)| *x == name)
+                .map(|(_, value)| (value, end + 2))
+        });
+        match value {
+            Some((value, len)) => {
+                out.push_str(value);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// tr!("tasks_added", count = 2) is "added 2 tasks" in english
+macro_rules! tr {
+    ($key:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::translate($key, &[$((stringify!($name), $value.to_string())),*])
+    };
+}
+pub(crate) use tr;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    // the {name} placeholders of a message
+    fn placeholders(message: &str) -> BTreeSet<&str> {
+        message
+            .split('{')
+            .skip(1)
+            .filter_map(|x| x.split_once('}').map(|(name, _)| name))
+            .filter(|x| !x.is_empty() && x.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+            .collect()
+    }
+
+    // the plural categories of the language
+    fn plurals(locale: Locale) -> &'static [Plural] {
+        match locale {
+            Locale::En | Locale::De => &[Plural::One, Plural::Other],
+        }
+    }
+
+    // "tasks_added.one" -> "tasks_added"
+    fn base_key(key: &str) -> &str {
+        key.split_once('.').map_or(key, |(base, _)| base)
+    }
+
+    #[test]
+    fn all_keys_translated() {
+        let english: BTreeSet<&str> = en::MESSAGES.iter().map(|(x, _)| base_key(x)).collect();
+        for locale in LOCALES {
+            let keys: BTreeSet<&str> = locale.messages().iter().map(|(x, _)| base_key(x)).collect();
+            assert_eq!(keys, english, "{:?} has other keys than english", locale);
+            let mut seen = BTreeSet::new();
+            for (key, message) in locale.messages() {
+                assert!(seen.insert(*key), "{:?} has {} twice", locale, key);
+                // a message has the same placeholders as the english one
+                let english_message = en::MESSAGES
+                    .iter()
+                    .find(|(x, _)| base_key(x) == base_key(key))
+                    .map(|(_, message)| *message)
+                    .unwrap();
+                assert_eq!(
+                    placeholders(message),
+                    placeholders(english_message),
+                    "{:?} {}",
+                    locale,
+                    key
+                );
+            }
+            // a plural message has all the categories of the locale
+            for key in keys.iter() {
+                let plural = locale.lookup(key).is_none();
+                for category in plurals(locale).iter().filter(|_| plural) {
+                    let key = format!("{}.{}", key, category.suffix());
+                    assert!(locale.lookup(&key).is_some(), "{:?} misses {}", locale, key);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn used_keys_exist() {
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        for entry in std::fs::read_dir(src).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|x| x != "rs") {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path).unwrap();
+            for part in content.split("tr!(\"").skip(1) {
+                let key = part.split('"').next().unwrap();
+                let found = Locale::En.lookup(key).is_some()
+                    || Locale::En.lookup(&format!("{}.other", key)).is_some();
+                assert!(found, "{:?} uses the unknown key {}", path, key);
+            }
+        }
+    }
+
+    // the string literals of the code before its tests, comments are skipped
+    fn literals(content: &str) -> Vec<String> {
+        let code = content.split("#[cfg(test)]").next().unwrap();
+        let chars: Vec<char> = code.chars().collect();
+        let mut out = vec![];
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                '/' if chars.get(i + 1) == Some(&'/') => {
+                    while i < chars.len() && chars[i] != '\n' {
+                        i += 1;
+                    }
+                }
+                // a char literal like '"' or '\'', not a lifetime
+                '\'' if chars.get(i + 2) == Some(&'\'') => i += 2,
+                '\'' if chars.get(i + 1) == Some(&'\\') => {
+                    i += 2;
+                    while chars[i] != '\'' {
+                        i += 1;
+                    }
+                }
+                '"' => {
+                    let mut literal = String::new();
+                    i += 1;
+                    while chars[i] != '"' {
+                        if chars[i] == '\\' {
+                            i += 1;
+                        }
+                        literal.push(chars[i]);
+                        i += 1;
+                    }
+                    out.push(literal);
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        out
+    }
+
+    // text which is not a message, like the lines of file formats
+    const NOT_MESSAGES: &[&str] = &["digraph todo0 {\n", "less -R"];
+
+    #[test]
+    fn no_hardcoded_messages() {
+        let src = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let mut found = vec![];
+        for entry in std::fs::read_dir(src).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|x| x != "rs") {
+                continue;
+            }
+            let content = std::fs::read_to_string(&path).unwrap();
+            for literal in literals(&content) {
+                // two words in a row or a label like "tasks: {}" are a message,
+                // which belongs in the catalog
+                let is_word = |word: &str| {
+                    let word = word.trim_end_matches([':', ',']);
+                    word.len() > 1 && word.chars().all(|c| c.is_alphabetic())
+                };
+                let words: Vec<&str> = literal.split(' ').collect();
+                let is_message = words.windows(2).any(|x| x.iter().all(|word| is_word(word)))
+                    || words.iter().any(|word| {
+                        let label = word.strip_suffix(':').unwrap_or("");
+                        label.len() > 2 && label.chars().all(|c| c.is_lowercase())
+                    });
+                if is_message && !NOT_MESSAGES.contains(&literal.as_str()) {
+                    let name = path.file_name().unwrap().to_string_lossy().to_string();
+                    found.push(format!("{}: {:?}", name, literal));
+                }
+            }
+        }
+        assert!(
+            found.is_empty(),
+            "messages which are not translated:\n{}",
+            found.join("\n")
+        );
+    }
+
+    #[test]
+    fn plural_forms() {
+        let args = |count: u64| [("count", count.to_string())];
+        assert_eq!(
+            translate_to(Locale::En, "tasks_added", &args(1)),
+            "added 1 task"
+        );
+        assert_eq!(
+            translate_to(Locale::En, "tasks_added", &args(0)),
+            "added 0 tasks"
+        );
+        assert_eq!(
+            translate_to(Locale::De, "tasks_added", &args(1)),
+            "1 Aufgabe hinzugefügt"
+        );
+        assert_eq!(
+            translate_to(Locale::De, "tasks_added", &args(3)),
+            "3 Aufgaben hinzugefügt"
+        );
+        // unknown keys are shown as they are
+        assert_eq!(translate_to(Locale::De, "no_such_key", &[]), "no_such_key");
+    }
+
+    #[test]
+    fn fill_placeholders() {
+        let args = [
+            ("title", String::from("fix {count}")),
+            ("count", String::from("2")),
+        ];
+        assert_eq!(
+            fill("{title}: {count} {{x}} {", &args),
+            "fix {count}: 2 {{x}} {"
+        );
+        assert_eq!(Locale::parse("de_AT.UTF-8"), Some(Locale::De));
+        assert_eq!(Locale::parse("en-GB"), Some(Locale::En));
+        assert_eq!(Locale::parse("C"), None);
+    }
+}
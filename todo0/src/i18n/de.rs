@@ -0,0 +1,243 @@
+// the german messages
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    ("short_help", "„{app} help“ oder „{app} --help“ zeigt die Verwendung."),
+    ("invalid_arguments", "Ungültige Argumente. {help}"),
+    ("unknown_command", "Unbekannter Befehl. {help}"),
+    ("missing_title", "Der Titel der Aufgabe fehlt. {help}"),
+    ("no_tasks", "Es gibt keine Aufgaben."),
+    ("tasks_added.one", "{count} Aufgabe hinzugefügt"),
+    ("tasks_added.other", "{count} Aufgaben hinzugefügt"),
+    ("tasks_edited.one", "{count} Aufgabe bearbeitet"),
+    ("tasks_edited.other", "{count} Aufgaben bearbeitet"),
+    ("tasks_deleted.one", "{count} Aufgabe gelöscht"),
+    ("tasks_deleted.other", "{count} Aufgaben gelöscht"),
+    ("all_deleted", "alle Aufgaben gelöscht"),
+    ("tasks_checked.one", "{count} Aufgabe als erledigt markiert"),
+    ("tasks_checked.other", "{count} Aufgaben als erledigt markiert"),
+    ("tasks_unchecked.one", "{count} Aufgabe als nicht erledigt markiert"),
+    ("tasks_unchecked.other", "{count} Aufgaben als nicht erledigt markiert"),
+    ("still_blocked", "Warnung: „{title}“ ist noch blockiert durch {blockers}"),
+    ("tasks_assigned.one", "{count} Aufgabe an {user} zugewiesen"),
+    ("tasks_assigned.other", "{count} Aufgaben an {user} zugewiesen"),
+    ("tasks_unassigned.one", "Zuweisung von {count} Aufgabe entfernt"),
+    ("tasks_unassigned.other", "Zuweisung von {count} Aufgaben entfernt"),
+    ("unknown_user", "unbekannter Benutzer, \"user\" in der Konfiguration oder TODO0_USER setzen"),
+    ("invalid_priority", "ungültige Priorität {priority}, erwartet wird high, medium, low oder none"),
+    ("priority_set.one", "Priorität von {count} Aufgabe auf {priority} gesetzt"),
+    ("priority_set.other", "Priorität von {count} Aufgaben auf {priority} gesetzt"),
+    ("tasks_moved.one", "{count} Aufgabe nach {state} verschoben"),
+    ("tasks_moved.other", "{count} Aufgaben nach {state} verschoben"),
+    ("invalid_date", "ungültiges Datum {date}, erwartet wird JJJJ-MM-TT oder z. B. yesterday"),
+    ("index_out_of_range", "Position {index} liegt außerhalb der Liste (höchstens {max})"),
+    ("reversed_range", "ungültiger Bereich {range}, die erste Position darf nicht nach der letzten liegen"),
+    ("unknown_sort", "unbekannte Sortierung {sort}, erwartet wird urgency"),
+    ("interpreted_title", "Titel:  {title}"),
+    ("interpreted_no_due", "Fällig: -"),
+    ("interpreted_due", "Fällig: {due} (aus „{phrase}“)"),
+    ("task_started", "„{title}“ gestartet"),
+    ("task_stopped", "„{title}“ gestoppt"),
+    ("task_stopped_total", "„{title}“ gestoppt ({total} insgesamt)"),
+    ("no_task_started", "es ist keine Aufgabe gestartet"),
+    ("task_blocked", "„{title}“ ist blockiert durch „{blocker}“"),
+    ("tasks_unblocked.one", "Blockierung von {count} Aufgabe aufgehoben"),
+    ("tasks_unblocked.other", "Blockierung von {count} Aufgaben aufgehoben"),
+    ("nothing_ready", "es gibt keine Aufgaben, an denen gearbeitet werden kann"),
+    ("urgency", "Dringlichkeit {total}"),
+    ("urgency_parts", "Dringlichkeit {total}: {parts}"),
+    ("comments_found.one", "{count} Kommentar gefunden: {added} hinzugefügt, {updated} aktualisiert, {reopened} wieder geöffnet, {done} erledigt"),
+    ("comments_found.other", "{count} Kommentare gefunden: {added} hinzugefügt, {updated} aktualisiert, {reopened} wieder geöffnet, {done} erledigt"),
+    ("no_plugins", "keine Plugins in {dir}"),
+    ("template.one", "{name} ({count} Aufgabe)"),
+    ("template.other", "{name} ({count} Aufgaben)"),
+    ("template_saved.one", "Vorlage {name} mit {count} Aufgabe gespeichert"),
+    ("template_saved.other", "Vorlage {name} mit {count} Aufgaben gespeichert"),
+    ("template_deleted", "Vorlage {name} gelöscht"),
+    ("unknown_template", "unbekannte Vorlage {name}"),
+    ("unknown_export_format", "unbekanntes Format {format}, erwartet wird ics oder org"),
+    ("unknown_import_format", "unbekanntes Format von {file}, erwartet wird .ics oder .org"),
+    ("import_failed", "Import von {file} fehlgeschlagen: {error}"),
+    ("tasks_imported.one", "{count} Aufgabe importiert: {added} hinzugefügt, {updated} aktualisiert"),
+    ("tasks_imported.other", "{count} Aufgaben importiert: {added} hinzugefügt, {updated} aktualisiert"),
+    ("not_in_batch", "im Stapelmodus nicht verfügbar"),
+    ("script_error", "Zeile {line}: {command}: {error}"),
+    ("nothing_saved", "es wurden keine Änderungen gespeichert"),
+    ("not_in_shell", "in der Shell nicht verfügbar"),
+    ("reloaded", "Änderungen eines anderen Prozesses geladen"),
+    ("merged", "Änderungen eines anderen Prozesses in {file} zusammengeführt"),
+    ("not_merged", "{error}\ndie Datei wurde von einem anderen Prozess geändert und kann nicht gelesen werden, es wurden keine Änderungen gespeichert"),
+    ("not_in_markdown", "für Markdown-Dateien nicht verfügbar, sie speichern nur den Titel und ob eine Aufgabe erledigt ist"),
+    ("not_in_org", "für Org-Dateien nicht verfügbar, sie speichern keine Zuweisungen, erfasste Zeiten oder Blockaden"),
+    ("no_org_keyword", "die Org-Datei hat kein TODO-Schlüsselwort für den Zustand {state}"),
+    ("document_changed", "{file} wurde von einem anderen Prozess geändert, es wurden keine Änderungen gespeichert"),
+    ("document_not_encryptable", "Markdown- und Org-Dateien können nicht verschlüsselt werden"),
+    ("already_encrypted", "die Datei ist bereits verschlüsselt"),
+    ("not_encrypted", "die Datei ist nicht verschlüsselt"),
+    ("encrypted", "{file} verschlüsselt"),
+    ("decrypted", "{file} entschlüsselt"),
+    ("no_snapshots", "noch keine Schnappschüsse"),
+    ("snapshot.one", "{index}. {name}  {count} Aufgabe, {done} erledigt"),
+    ("snapshot.other", "{index}. {name}  {count} Aufgaben, {done} erledigt"),
+    ("snapshot_name", "{index}. {name}"),
+    ("snapshot_failed", "Schnappschuss fehlgeschlagen: {error}"),
+    ("tasks_restored.one", "{count} Aufgabe aus Schnappschuss {name} wiederhergestellt"),
+    ("tasks_restored.other", "{count} Aufgaben aus Schnappschuss {name} wiederhergestellt"),
+    ("no_changes", "keine Änderungen seit Schnappschuss {name}"),
+    ("change_added", "hinzugefügt     {pos}. {title}"),
+    ("change_removed", "entfernt        {title}"),
+    ("change_renamed", "umbenannt       {pos}. {from} -> {to}"),
+    ("change_checked", "erledigt        {pos}. {title}"),
+    ("change_unchecked", "nicht erledigt  {pos}. {title}"),
+    ("read_file_failed", "Datei {file} konnte nicht gelesen werden: {error}"),
+    ("create_dir_failed", "Verzeichnis {dir} konnte nicht angelegt werden: {error}"),
+    ("create_file_failed", "Datei {file} konnte nicht angelegt werden: {error}"),
+    ("open_file_failed", "Datei {file} konnte nicht geöffnet werden: {error}"),
+    ("read_dir_failed", "Verzeichnis {dir} konnte nicht gelesen werden: {error}"),
+    ("read_failed", "{path} konnte nicht gelesen werden: {error}"),
+    ("write_file_failed", "Datei {file} konnte nicht geschrieben werden: {error}"),
+    ("delete_failed", "{path} konnte nicht gelöscht werden: {error}"),
+    ("decode_json_failed", "JSON der Datei {file} konnte nicht gelesen werden: {error}"),
+    ("encode_json_failed", "JSON konnte nicht erzeugt werden: {error}"),
+    ("decrypt_file_failed", "Datei {file} konnte nicht entschlüsselt werden: {error}"),
+    ("backup_failed", "Sicherung nach {file} fehlgeschlagen: {error}"),
+    ("file_encrypted_no_key", "Datei {file} ist verschlüsselt, setze {env} oder konfiguriere eine Schlüsseldatei"),
+    ("file_encrypted", "Datei {file} ist verschlüsselt"),
+    ("file_not_encryptable", "Datei {file} kann nicht verschlüsselt werden"),
+    ("file_error", "Datei {file}: {error}"),
+    ("invalid_version", "ungültige Version {version}"),
+    ("newer_version", "Dateiversion {version} ist neuer als die unterstützte Version {supported}"),
+    ("migrate_failed", "Migration von Version {version} fehlgeschlagen: {error}"),
+    ("expected_object", "JSON-Objekt erwartet"),
+    ("expected_items", "Array von Einträgen erwartet"),
+    ("task_not_found", "Aufgabe nicht gefunden"),
+    ("title_empty", "der Titel ist leer"),
+    ("invalid_position", "ungültige Position"),
+    ("no_items", "keine Einträge"),
+    ("due_label", "(fällig {due})"),
+    ("block_itself", "eine Aufgabe kann sich nicht selbst blockieren"),
+    ("block_cycle", "\"{blocker}\" hängt bereits von \"{task}\" ab, das Blockieren würde einen Zyklus erzeugen"),
+    ("already_started", "Aufgabe \"{title}\" ist bereits gestartet"),
+    ("untagged", "(ohne Tag)"),
+    ("report_total", "{duration}  gesamt"),
+    ("too_few_states", "der Workflow braucht mindestens 2 Zustände"),
+    ("unknown_transition_state", "unbekannter Zustand {state} in den Übergängen des Workflows"),
+    ("unknown_state", "unbekannter Zustand {state}, erwartet wird einer von: {states}"),
+    ("invalid_transition", "\"{title}\" kann nicht von {from} nach {to} verschoben werden"),
+    ("unknown_snapshot", "unbekannter Schnappschuss {name}, siehe \"snapshots\" für die Liste"),
+    ("run_hook_failed", "{event}-Hook {hook} konnte nicht ausgeführt werden: {error}"),
+    ("hook_rejected", "{event}-Hook {hook} hat die Aufgabe \"{title}\" abgelehnt ({status})"),
+    ("warning", "Warnung: {error}"),
+    ("invalid_request", "ungültige Anfrage {request}: {error}"),
+    ("position_out_of_range", "Position {position} liegt außerhalb des Bereichs (max. {max})"),
+    ("plugins_disabled", "Plugins benötigen ein mit --features plugins gebautes todo0"),
+    ("unknown_format_no_plugins", "unbekanntes Format {format}, Plugins benötigen ein mit --features plugins gebautes todo0"),
+    ("no_renderer", "unbekanntes Format {format}, kein Plugin gibt es aus"),
+    ("load_plugin_failed", "Plugin {plugin} konnte nicht geladen werden: {error}"),
+    ("plugin_failed", "Plugin {plugin} ist in {function} fehlgeschlagen: {error}"),
+    ("invalid_describe", "ungültige describe-Ausgabe von Plugin {plugin}: {error}"),
+    ("ical_invalid_line", "ungültige Zeile {line}"),
+    ("line_error", "Zeile {line}: {error}"),
+    ("vtodo_without_summary", "VTODO ohne SUMMARY"),
+    ("vtodo_without_end", "VTODO ohne END"),
+    ("ical_invalid_priority", "ungültige Priorität {priority}"),
+    ("ical_invalid_date", "ungültiges Datum {date}"),
+    ("unclosed_variable", "nicht geschlossene Variable in {text}"),
+    ("missing_var", "--var {name}=<wert> fehlt für {text}"),
+    ("invalid_variable", "ungültige Variable {var}, erwartet wird name=wert"),
+    ("unclosed_quote", "Zeile {line}: nicht geschlossenes Anführungszeichen"),
+    ("read_script_failed", "das Skript konnte nicht gelesen werden: {error}"),
+    ("invalid_config", "ungültige Konfiguration {file}: {error}"),
+    ("read_keyfile_failed", "Schlüsseldatei {file} konnte nicht gelesen werden: {error}"),
+    ("keyfile_empty", "Schlüsseldatei {file} ist leer"),
+    ("no_key", "kein Schlüssel, setze {env} oder konfiguriere eine Schlüsseldatei"),
+    ("derive_key_failed", "Schlüssel konnte nicht abgeleitet werden: {error}"),
+    ("encrypt_failed", "Verschlüsselung fehlgeschlagen"),
+    ("not_encrypted_file", "keine verschlüsselte Datei"),
+    ("unsupported_format", "nicht unterstütztes Verschlüsselungsformat {format}"),
+    ("wrong_key", "falsche Passphrase oder falscher Schlüssel, oder die Datei ist beschädigt"),
+    ("start_shell_failed", "die Shell konnte nicht gestartet werden: {error}"),
+    ("read_line_failed", "die Zeile konnte nicht gelesen werden: {error}"),
+    ("save_history_failed", "der Verlauf konnte nicht in {file} gespeichert werden: {error}"),
+    ("watch_failed", "die Datei konnte nicht beobachtet werden: {error}"),
+    ("watch_file_failed", "{file} konnte nicht beobachtet werden: {error}"),
+    ("invalid_file", "ungültige Datei {file}"),
+    ("watcher_stopped", "die Dateibeobachtung wurde beendet"),
+    ("stats_total", "Aufgaben: {total}, erledigt: {done} ({rate} %)"),
+    ("stats_average", "durchschnittliche Zeit bis erledigt: {time}"),
+    ("stats_by_state", "nach Zustand:   {counts}"),
+    ("stats_by_tag", "nach Tag:       {counts}"),
+    ("stats_by_priority", "nach Priorität: {counts}"),
+    ("stats_burndown", "Burndown {from} .. {to}"),
+    (
+        "help",
+        r#"Verwendung:
+  {app}                : Zeigt die Liste der Aufgaben
+  {app} list           : Zeigt die Liste der Aufgaben
+  {app} list --mine    : Zeigt die Aufgaben, die dem aktuellen Benutzer zugewiesen sind
+  {app} list --truncate: Kürzt lange Titel auf die Breite des Terminals, statt sie umzubrechen
+  {app} list --follow  : Zeigt die Aufgaben erneut, sobald sich die Datei ändert
+  {app} list --format html
+                       : Zeigt die Aufgaben mit dem Renderer "html" eines Plugins
+  {app} reset          : Löscht alle Aufgaben
+  {app} add hello foo  : Fügt die Aufgabe "hello foo" am Ende hinzu
+  {app} add 2 foo bar  : Fügt die Aufgabe "foo bar" an Position 2 hinzu
+  {app} add call bob next fri 9am
+                       : Fügt die Aufgabe "call bob" hinzu, fällig nächsten Freitag um 9:00
+  {app} add --dry-run pay rent by end of month
+                       : Zeigt, wie Titel und Fälligkeit gelesen werden, ohne hinzuzufügen
+  {app} edit bar       : Ändert die letzte Aufgabe zu "bar"
+  {app} edit 1 bar     : Ändert die Aufgabe an Position 1 zu "bar"
+  {app} del 2 1        : Löscht die zweite und die erste Aufgabe
+  {app} del last       : Löscht die letzte Aufgabe
+  {app} del done       : Löscht alle erledigten Aufgaben
+  {app} check 1 2      : Markiert die Aufgaben an Position 1 und 2 als erledigt
+  {app} uncheck 1 2    : Markiert die Aufgaben an Position 1 und 2 als nicht erledigt
+  {app} mv-state 1 review
+                       : Verschiebt die Aufgabe an Position 1 in den Zustand "review"
+  {app} board          : Zeigt die Aufgaben in Spalten nach Zustand
+  {app} assign 4 alice : Weist die Aufgabe an Position 4 alice zu
+  {app} unassign 4     : Entfernt die Zuweisung der Aufgabe an Position 4
+  {app} prio 4 high    : Setzt die Priorität der Aufgabe an Position 4 (high, medium, low, none)
+  {app} stats          : Zeigt die Anzahl nach Zustand, Tag und Priorität und den Burndown der letzten 14 Tage
+  {app} stats --since 2024-03-01 --until 2024-03-31 --json
+                       : Zeigt die Statistik eines Zeitraums als JSON
+  {app} block 5 on 3   : Markiert die Aufgabe an Position 5 als blockiert durch die Aufgabe an Position 3
+  {app} unblock 5 on 3 : Entfernt die Aufgabe an Position 3 aus den Blockern der Aufgabe an Position 5
+  {app} unblock 5      : Entfernt alle Blocker der Aufgabe an Position 5
+  {app} ready          : Zeigt die Aufgaben, die weder erledigt noch blockiert sind
+  {app} next           : Zeigt die bereite Aufgabe mit der höchsten Dringlichkeit
+  {app} list --sort urgency
+                       : Zeigt die Aufgaben nach Dringlichkeit, mit den Teilen des Werts
+  {app} graph          : Gibt die Abhängigkeiten als Graphviz-Dot-Graph aus
+  {app} start 2        : Startet die Zeiterfassung der Aufgabe an Position 2
+  {app} stop           : Stoppt die Zeiterfassung
+  {app} report         : Zeigt die erfasste Zeit nach Aufgabe
+  {app} report --since 2024-03-01 --by tag --csv
+                       : Zeigt die erfasste Zeit seit einem Datum nach Tag, als CSV
+  {app} template save release 3-9 --var version=1.2
+                       : Speichert die Aufgaben 3 bis 9 als Vorlage, mit 1.2 als {{version}}
+  {app} template apply release --var version=1.3
+                       : Fügt die Aufgaben der Vorlage hinzu
+  {app} template list  : Zeigt die Vorlagen
+  {app} template del release
+                       : Löscht die Vorlage
+  {app} export --format ics
+                       : Gibt die Aufgaben als iCalendar-VTODOs aus
+  {app} import x.ics   : Fügt die Aufgaben der iCalendar-Datei hinzu oder aktualisiert vorhandene
+  {app} export --format org
+                       : Gibt die Aufgaben als Org-mode-Überschriften aus
+  {app} import x.org   : Fügt die TODO-Überschriften der Org-Datei hinzu oder aktualisiert vorhandene
+  {app} scan src       : Gleicht die TODO/FIXME/XXX-Kommentare in src mit src/.todo0-scan.json ab
+  {app} batch < script : Führt die Befehle des Skripts aus, einen pro Zeile, und speichert nur, wenn alle gelingen
+  {app} -c "add x; check 2"
+                       : Führt die durch ";" getrennten Befehle ebenso aus
+  {app} shell          : Führt Befehle in einer interaktiven Shell mit Verlauf und Vervollständigung aus
+  {app} plugins        : Zeigt die Plugins mit ihren Befehlen und Renderern
+  {app} snapshots      : Zeigt die Schnappschüsse der Liste, den neuesten zuerst
+  {app} diff 2         : Zeigt die seit Schnappschuss 2 hinzugefügten, entfernten, umbenannten, erledigten oder wieder geöffneten Aufgaben
+  {app} restore 2      : Ersetzt die Liste durch Schnappschuss 2
+  {app} encrypt        : Verschlüsselt die Datei mit $TODO0_PASSPHRASE oder der Schlüsseldatei
+  {app} decrypt        : Entschlüsselt die Datei
+"#,
+    ),
+];
@@ -0,0 +1,243 @@
+// the english messages, the reference for the other locales
+
+pub const MESSAGES: &[(&str, &str)] = &[
+    ("short_help", "Use \"{app} help\" or \"{app} --help\" to see the usage."),
+    ("invalid_arguments", "Invalid arguments. {help}"),
+    ("unknown_command", "Unknown command. {help}"),
+    ("missing_title", "Missing task title. {help}"),
+    ("no_tasks", "There are no tasks."),
+    ("tasks_added.one", "added {count} task"),
+    ("tasks_added.other", "added {count} tasks"),
+    ("tasks_edited.one", "edited {count} task"),
+    ("tasks_edited.other", "edited {count} tasks"),
+    ("tasks_deleted.one", "deleted {count} task"),
+    ("tasks_deleted.other", "deleted {count} tasks"),
+    ("all_deleted", "deleted all tasks"),
+    ("tasks_checked.one", "marked {count} task as done"),
+    ("tasks_checked.other", "marked {count} tasks as done"),
+    ("tasks_unchecked.one", "marked {count} task as not done"),
+    ("tasks_unchecked.other", "marked {count} tasks as not done"),
+    ("still_blocked", "warning: \"{title}\" is still blocked by {blockers}"),
+    ("tasks_assigned.one", "assigned {count} task to {user}"),
+    ("tasks_assigned.other", "assigned {count} tasks to {user}"),
+    ("tasks_unassigned.one", "unassigned {count} task"),
+    ("tasks_unassigned.other", "unassigned {count} tasks"),
+    ("unknown_user", "unknown user, set \"user\" in the config or TODO0_USER"),
+    ("invalid_priority", "invalid priority {priority}, expected high, medium, low or none"),
+    ("priority_set.one", "set priority of {count} task to {priority}"),
+    ("priority_set.other", "set priority of {count} tasks to {priority}"),
+    ("tasks_moved.one", "moved {count} task to {state}"),
+    ("tasks_moved.other", "moved {count} tasks to {state}"),
+    ("invalid_date", "invalid date {date}, expected YYYY-MM-DD or e.g. yesterday"),
+    ("index_out_of_range", "index {index} is out of range (max {max})"),
+    ("reversed_range", "invalid range {range}, the first index must not be after the last"),
+    ("unknown_sort", "unknown sort {sort}, expected urgency"),
+    ("interpreted_title", "title: {title}"),
+    ("interpreted_no_due", "due:   -"),
+    ("interpreted_due", "due:   {due} (from \"{phrase}\")"),
+    ("task_started", "started \"{title}\""),
+    ("task_stopped", "stopped \"{title}\""),
+    ("task_stopped_total", "stopped \"{title}\" ({total} total)"),
+    ("no_task_started", "no task is started"),
+    ("task_blocked", "\"{title}\" is blocked by \"{blocker}\""),
+    ("tasks_unblocked.one", "unblocked {count} task"),
+    ("tasks_unblocked.other", "unblocked {count} tasks"),
+    ("nothing_ready", "there are no tasks ready to work on"),
+    ("urgency", "urgency {total}"),
+    ("urgency_parts", "urgency {total}: {parts}"),
+    ("comments_found.one", "found {count} comment: {added} added, {updated} updated, {reopened} reopened, {done} done"),
+    ("comments_found.other", "found {count} comments: {added} added, {updated} updated, {reopened} reopened, {done} done"),
+    ("no_plugins", "no plugins in {dir}"),
+    ("template.one", "{name} ({count} task)"),
+    ("template.other", "{name} ({count} tasks)"),
+    ("template_saved.one", "saved template {name} with {count} task"),
+    ("template_saved.other", "saved template {name} with {count} tasks"),
+    ("template_deleted", "deleted template {name}"),
+    ("unknown_template", "unknown template {name}"),
+    ("unknown_export_format", "unknown format {format}, expected ics or org"),
+    ("unknown_import_format", "unknown format of {file}, expected .ics or .org"),
+    ("import_failed", "failed to import {file}: {error}"),
+    ("tasks_imported.one", "imported {count} task: {added} added, {updated} updated"),
+    ("tasks_imported.other", "imported {count} tasks: {added} added, {updated} updated"),
+    ("not_in_batch", "not available in batch mode"),
+    ("script_error", "line {line}: {command}: {error}"),
+    ("nothing_saved", "no changes were saved"),
+    ("not_in_shell", "not available in the shell"),
+    ("reloaded", "loaded the changes of another process"),
+    ("merged", "merged the changes of another process to {file}"),
+    ("not_merged", "{error}\nthe file was changed by another process and can not be read, no changes were saved"),
+    ("not_in_markdown", "not available for markdown files, they only store the title and whether a task is done"),
+    ("not_in_org", "not available for org files, they do not store assignees, tracked time or blockers"),
+    ("no_org_keyword", "the org file has no TODO keyword for the state {state}"),
+    ("document_changed", "{file} was changed by another process, no changes were saved"),
+    ("document_not_encryptable", "markdown and org files can not be encrypted"),
+    ("already_encrypted", "the file is already encrypted"),
+    ("not_encrypted", "the file is not encrypted"),
+    ("encrypted", "encrypted {file}"),
+    ("decrypted", "decrypted {file}"),
+    ("no_snapshots", "no snapshots yet"),
+    ("snapshot.one", "{index}. {name}  {count} task, {done} done"),
+    ("snapshot.other", "{index}. {name}  {count} tasks, {done} done"),
+    ("snapshot_name", "{index}. {name}"),
+    ("snapshot_failed", "failed to take a snapshot: {error}"),
+    ("tasks_restored.one", "restored {count} task from snapshot {name}"),
+    ("tasks_restored.other", "restored {count} tasks from snapshot {name}"),
+    ("no_changes", "no changes since snapshot {name}"),
+    ("change_added", "added      {pos}. {title}"),
+    ("change_removed", "removed    {title}"),
+    ("change_renamed", "renamed    {pos}. {from} -> {to}"),
+    ("change_checked", "checked    {pos}. {title}"),
+    ("change_unchecked", "unchecked  {pos}. {title}"),
+    ("read_file_failed", "failed to read file {file}: {error}"),
+    ("create_dir_failed", "failed to create dir {dir}: {error}"),
+    ("create_file_failed", "failed to create file {file}: {error}"),
+    ("open_file_failed", "failed to open file {file}: {error}"),
+    ("read_dir_failed", "failed to read dir {dir}: {error}"),
+    ("read_failed", "failed to read {path}: {error}"),
+    ("write_file_failed", "failed to write to file {file}: {error}"),
+    ("delete_failed", "failed to delete {path}: {error}"),
+    ("decode_json_failed", "failed to decode json from file {file}: {error}"),
+    ("encode_json_failed", "failed to encode json: {error}"),
+    ("decrypt_file_failed", "failed to decrypt file {file}: {error}"),
+    ("backup_failed", "failed to backup file to {file}: {error}"),
+    ("file_encrypted_no_key", "file {file} is encrypted, set {env} or configure a keyfile"),
+    ("file_encrypted", "file {file} is encrypted"),
+    ("file_not_encryptable", "file {file} can not be encrypted"),
+    ("file_error", "file {file}: {error}"),
+    ("invalid_version", "invalid version {version}"),
+    ("newer_version", "file version {version} is newer than the supported version {supported}"),
+    ("migrate_failed", "failed to migrate from version {version}: {error}"),
+    ("expected_object", "expected a json object"),
+    ("expected_items", "expected an array of items"),
+    ("task_not_found", "task not found"),
+    ("title_empty", "title is empty"),
+    ("invalid_position", "invalid position"),
+    ("no_items", "no items"),
+    ("due_label", "(due {due})"),
+    ("block_itself", "a task can not block itself"),
+    ("block_cycle", "\"{blocker}\" already depends on \"{task}\", blocking would create a cycle"),
+    ("already_started", "task \"{title}\" is already started"),
+    ("untagged", "(untagged)"),
+    ("report_total", "{duration}  total"),
+    ("too_few_states", "workflow needs at least 2 states"),
+    ("unknown_transition_state", "unknown state {state} in workflow transitions"),
+    ("unknown_state", "unknown state {state}, expected one of: {states}"),
+    ("invalid_transition", "can not move \"{title}\" from {from} to {to}"),
+    ("unknown_snapshot", "unknown snapshot {name}, see \"snapshots\" for the list"),
+    ("run_hook_failed", "failed to run {event} hook {hook}: {error}"),
+    ("hook_rejected", "{event} hook {hook} rejected the task \"{title}\" ({status})"),
+    ("warning", "warning: {error}"),
+    ("invalid_request", "invalid request {request}: {error}"),
+    ("position_out_of_range", "position {position} is out of range (max {max})"),
+    ("plugins_disabled", "plugins need todo0 built with --features plugins"),
+    ("unknown_format_no_plugins", "unknown format {format}, plugins need todo0 built with --features plugins"),
+    ("no_renderer", "unknown format {format}, no plugin renders it"),
+    ("load_plugin_failed", "failed to load plugin {plugin}: {error}"),
+    ("plugin_failed", "plugin {plugin} failed in {function}: {error}"),
+    ("invalid_describe", "invalid describe output of plugin {plugin}: {error}"),
+    ("ical_invalid_line", "invalid line {line}"),
+    ("line_error", "line {line}: {error}"),
+    ("vtodo_without_summary", "VTODO without SUMMARY"),
+    ("vtodo_without_end", "VTODO without END"),
+    ("ical_invalid_priority", "invalid priority {priority}"),
+    ("ical_invalid_date", "invalid date {date}"),
+    ("unclosed_variable", "unclosed variable in {text}"),
+    ("missing_var", "missing --var {name}=<value> for {text}"),
+    ("invalid_variable", "invalid variable {var}, expected name=value"),
+    ("unclosed_quote", "line {line}: unclosed quote"),
+    ("read_script_failed", "failed to read the script: {error}"),
+    ("invalid_config", "invalid config {file}: {error}"),
+    ("read_keyfile_failed", "failed to read keyfile {file}: {error}"),
+    ("keyfile_empty", "keyfile {file} is empty"),
+    ("no_key", "no key, set {env} or configure a keyfile"),
+    ("derive_key_failed", "failed to derive key: {error}"),
+    ("encrypt_failed", "failed to encrypt"),
+    ("not_encrypted_file", "not an encrypted file"),
+    ("unsupported_format", "unsupported encryption format {format}"),
+    ("wrong_key", "wrong passphrase or key, or the file is corrupted"),
+    ("start_shell_failed", "failed to start the shell: {error}"),
+    ("read_line_failed", "failed to read the line: {error}"),
+    ("save_history_failed", "failed to save the history to {file}: {error}"),
+    ("watch_failed", "failed to watch the file: {error}"),
+    ("watch_file_failed", "failed to watch {file}: {error}"),
+    ("invalid_file", "invalid file {file}"),
+    ("watcher_stopped", "the file watcher stopped"),
+    ("stats_total", "tasks: {total}, done: {done} ({rate}%)"),
+    ("stats_average", "average time to done: {time}"),
+    ("stats_by_state", "by state:    {counts}"),
+    ("stats_by_tag", "by tag:      {counts}"),
+    ("stats_by_priority", "by priority: {counts}"),
+    ("stats_burndown", "burndown {from} .. {to}"),
+    (
+        "help",
+        r#"Usage:
+  {app}                : Show the list of tasks
+  {app} list           : Show the list of tasks
+  {app} list --mine    : Show the tasks assigned to the current user
+  {app} list --truncate: Cut long titles at the terminal width instead of wrapping them
+  {app} list --follow  : Show the tasks again whenever the file changes
+  {app} list --format html
+                       : Show the tasks with the "html" renderer of a plugin
+  {app} reset          : Delete all tasks
+  {app} add hello foo  : Add a new task "hello foo" at the end
+  {app} add 2 foo bar  : Add a new task "foo bar" at position 2
+  {app} add call bob next fri 9am
+                       : Add a new task "call bob" due next Friday at 9:00
+  {app} add --dry-run pay rent by end of month
+                       : Show how the title and the due date are read, without adding
+  {app} edit bar       : Edit the last task, set to "bar"
+  {app} edit 1 bar     : Edit the task at position 1, set to "bar"
+  {app} del 2 1        : Delete the second and first tasks
+  {app} del last       : Delete the last task
+  {app} del done       : Delete all tasks marked as done
+  {app} check 1 2      : Mark the tasks at position 1 and 2 as done
+  {app} uncheck 1 2    : Mark the tasks at position 1 and 2 as not done
+  {app} mv-state 1 review
+                       : Move the task at position 1 to the "review" state
+  {app} board          : Show the tasks in columns by state
+  {app} assign 4 alice : Assign the task at position 4 to alice
+  {app} unassign 4     : Remove the assignee of the task at position 4
+  {app} prio 4 high    : Set the priority of the task at position 4 (high, medium, low, none)
+  {app} stats          : Show counts by state, tag and priority, and the burndown of the last 14 days
+  {app} stats --since 2024-03-01 --until 2024-03-31 --json
+                       : Show the statistics of a date range as json
+  {app} block 5 on 3   : Mark the task at position 5 as blocked by the task at position 3
+  {app} unblock 5 on 3 : Remove the task at position 3 from the blockers of the task at position 5
+  {app} unblock 5      : Remove all blockers of the task at position 5
+  {app} ready          : Show the tasks which are not done and not blocked
+  {app} next           : Show the ready task with the highest urgency score
+  {app} list --sort urgency
+                       : Show the tasks by urgency score, with the parts of the score
+  {app} graph          : Print the dependencies as a graphviz dot graph
+  {app} start 2        : Start tracking time on the task at position 2
+  {app} stop           : Stop tracking time
+  {app} report         : Show tracked time by task
+  {app} report --since 2024-03-01 --by tag --csv
+                       : Show tracked time since a date by tag, as csv
+  {app} template save release 3-9 --var version=1.2
+                       : Save the tasks 3 to 9 as template, with 1.2 as {{version}}
+  {app} template apply release --var version=1.3
+                       : Add the tasks of the template
+  {app} template list  : Show the templates
+  {app} template del release
+                       : Delete the template
+  {app} export --format ics
+                       : Print the tasks as iCalendar VTODOs
+  {app} import x.ics   : Add the tasks of the iCalendar file, or update them if they exist
+  {app} export --format org
+                       : Print the tasks as Org-mode headings
+  {app} import x.org   : Add the TODO headings of the Org file, or update them if they exist
+  {app} scan src       : Sync the TODO/FIXME/XXX comments in src into src/.todo0-scan.json
+  {app} batch < script : Run the commands of the script, one per line, and save only if all succeed
+  {app} -c "add x; check 2"
+                       : Run the commands separated by ";" the same way
+  {app} shell          : Run commands in an interactive shell with history and completion
+  {app} plugins        : Show the plugins with their commands and renderers
+  {app} snapshots      : Show the snapshots of the list, newest first
+  {app} diff 2         : Show the tasks added, removed, renamed, checked or unchecked since snapshot 2
+  {app} restore 2      : Replace the list with snapshot 2
+  {app} encrypt        : Encrypt the file with $TODO0_PASSPHRASE or the keyfile
+  {app} decrypt        : Decrypt the file
+"#,
+    ),
+];
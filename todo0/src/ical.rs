@@ -0,0 +1,401 @@
+use crate::i18n::tr;
+use crate::todo::*;
+use crate::workflow::Workflow;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+const UID_SUFFIX: &str = "@todo0";
+const DATE_TIME_UTC: &str = "%Y%m%dT%H%M%SZ";
+const DATE_TIME: &str = "%Y%m%dT%H%M%S";
+const DATE: &str = "%Y%m%d";
+// content lines longer than this are folded
+const LINE_WIDTH: usize = 75;
+
+// the tasks as VTODO components of a VCALENDAR. the tags are written as
+// CATEGORIES and stay in the title. the tracked time and the scan source are
+// not exported.
+pub fn export_ics(list: &TodoList, workflow: &Workflow) -> String {
+    let now = Utc::now().format(DATE_TIME_UTC).to_string();
+    let mut lines = vec![
+        String::from("BEGIN:VCALENDAR"),
+        String::from("VERSION:2.0"),
+        String::from("PRODID:-//rustexp//todo0//EN"),
+    ];
+    for task in list.items.iter() {
+        lines.push(String::from("BEGIN:VTODO"));
+        lines.push(format!("UID:{}{}", task.id.0, UID_SUFFIX));
+        lines.push(format!("DTSTAMP:{}", now));
+        lines.push(format!("SUMMARY:{}", escape(&task.title)));
+        let state = workflow.state_of(task);
+        let status = if task.done {
+            "COMPLETED"
+        } else if state == workflow.initial() {
+            "NEEDS-ACTION"
+        } else {
+            "IN-PROCESS"
+        };
+        lines.push(format!("STATUS:{}", status));
+        lines.push(format!("X-TODO0-STATE:{}", escape(state)));
+        if let Some(due) = task.due {
+            lines.push(format_date_time("DUE", due));
+        }
+        let priority = match task.priority {
+            Priority::None => None,
+            Priority::High => Some(1),
+            Priority::Medium => Some(5),
+            Priority::Low => Some(9),
+        };
+        if let Some(priority) = priority {
+            lines.push(format!("PRIORITY:{}", priority));
+        }
+        let tags: Vec<_> = task.tags().iter().map(|x| escape(x)).collect();
+        if !tags.is_empty() {
+            lines.push(format!("CATEGORIES:{}", tags.join(",")));
+        }
+        if let Some(recurrence) = &task.recurrence {
+            lines.push(format!("RRULE:{}", recurrence));
+        }
+        if let Some(assignee) = &task.assignee {
+            lines.push(format!("X-TODO0-ASSIGNEE:{}", escape(assignee)));
+        }
+        for blocker in task.blocked_by.iter() {
+            lines.push(format!(
+                "RELATED-TO;RELTYPE=DEPENDS-ON:{}{}",
+                blocker.0, UID_SUFFIX
+            ));
+        }
+        if let Some(created) = task.created {
+            lines.push(format!(
+                "CREATED:{}",
+                created.with_timezone(&Utc).format(DATE_TIME_UTC)
+            ));
+        }
+        if let Some(done_at) = task.done_at {
+            lines.push(format!(
+                "COMPLETED:{}",
+                done_at.with_timezone(&Utc).format(DATE_TIME_UTC)
+            ));
+        }
+        lines.push(String::from("END:VTODO"));
+    }
+    lines.push(String::from("END:VCALENDAR"));
+
+    let mut buf = String::new();
+    for line in lines {
+        write!(buf, "{}\r\n", fold(&line)).unwrap();
+    }
+    buf
+}
+
+// the task in the list with the fields of a VTODO, the tracked time and the
+// source of a scanned comment are not exported and stay as they are
+pub fn update_from_ics(old: &Task, imported: Task) -> Task {
+    Task {
+        id: imported.id,
+        title: imported.title,
+        done: imported.done,
+        state: imported.state,
+        due: imported.due,
+        priority: imported.priority,
+        recurrence: imported.recurrence,
+        assignee: imported.assignee,
+        blocked_by: imported.blocked_by,
+        created: imported.created,
+        done_at: imported.done_at,
+        ..old.clone()
+    }
+}
+
+// read the VTODO components, other components are skipped. tasks exported by
+// todo0 keep their id, others get an id hashed from their UID so importing
+// the same file again updates the tasks instead of adding them twice.
+pub fn import_ics(content: &str, workflow: &Workflow) -> Result<Vec<Task>, String> {
+    let mut tasks = vec![];
+    let mut current: Option<Task> = None;
+    let mut status = String::new();
+    let mut categories: Vec<String> = vec![];
+    for (idx, line) in unfold(content).iter().enumerate() {
+        let err = |msg: String| tr!("line_error", line = idx + 1, error = msg);
+        let (name, params, value) =
+            parse_line(line).ok_or(err(tr!("ical_invalid_line", line = format!("{:?}", line))))?;
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value == "VTODO" => {
+                current = Some(Task::default());
+                status.clear();
+                categories.clear();
+            }
+            ("END", Some(_)) if value == "VTODO" => {
+                let mut task = current.take().unwrap();
+                if task.title.is_empty() {
+                    return Err(err(tr!("vtodo_without_summary")));
+                }
+                if task.id == TaskId(0) {
+                    task.id = TaskId::new();
+                }
+                for category in categories.iter() {
+                    if !task.tags().contains(&category.as_str()) {
+                        task.title = format!("{} #{}", task.title, category);
+                    }
+                }
+                task.done = status == "COMPLETED";
+                let state = match (task.state.as_str(), status.as_str()) {
+                    (state, _) if workflow.states.iter().any(|x| x == state) => state.to_string(),
+                    (_, "COMPLETED") => workflow.final_state().to_string(),
+                    (_, "IN-PROCESS") if workflow.states.len() > 2 => workflow.states[1].clone(),
+                    _ => workflow.initial().to_string(),
+                };
+                task.state = state;
+                if !task.done {
+                    task.done_at = None;
+                }
+                tasks.push(task);
+            }
+            (_, None) => {}
+            ("UID", Some(task)) => task.id = parse_uid(&value),
+            ("SUMMARY", Some(task)) => task.title = unescape(&value),
+            ("STATUS", Some(_)) => status = value.to_uppercase(),
+            ("X-TODO0-STATE", Some(task)) => task.state = unescape(&value),
+            ("DUE", Some(task)) => task.due = Some(parse_date_time(&params, &value).map_err(err)?),
+            ("PRIORITY", Some(task)) => {
+                task.priority = match value.parse::<u8>() {
+                    Ok(1..=4) => Priority::High,
+                    Ok(5) => Priority::Medium,
+                    Ok(6..=9) => Priority::Low,
+                    Ok(_) => Priority::None,
+                    Err(_) => {
+                        return Err(err(tr!(
+                            "ical_invalid_priority",
+                            priority = format!("{:?}", value)
+                        )))
+                    }
+                }
+            }
+            ("CATEGORIES", Some(_)) => {
+                categories.extend(split_list(&value).iter().map(|x| x.replace(' ', "-")))
+            }
+            ("RRULE", Some(task)) => task.recurrence = Some(value),
+            ("X-TODO0-ASSIGNEE", Some(task)) => task.assignee = Some(unescape(&value)),
+            ("RELATED-TO", Some(task)) if params.contains("RELTYPE=DEPENDS-ON") => {
+                task.blocked_by.push(parse_uid(&value))
+            }
+            ("CREATED", Some(task)) => {
+                task.created = Some(parse_date_time(&params, &value).map_err(err)?)
+            }
+            ("COMPLETED", Some(task)) => {
+                task.done_at = Some(parse_date_time(&params, &value).map_err(err)?)
+            }
+            _ => {}
+        }
+    }
+    if current.is_some() {
+        return Err(tr!("vtodo_without_end"));
+    }
+    Ok(tasks)
+}
+
+fn parse_uid(uid: &str) -> TaskId {
+    if let Some(id) = uid.strip_suffix(UID_SUFFIX).and_then(|x| x.parse().ok()) {
+        return TaskId(id);
+    }
+    let hash = Sha256::digest(uid.as_bytes());
+    TaskId(u64::from_be_bytes(hash[..8].try_into().unwrap()))
+}
+
+// a due date at midnight is written as a date, like format_due shows it
+fn format_date_time(name: &str, date: DateTime<Local>) -> String {
+    if date.time() == NaiveTime::MIN {
+        format!("{};VALUE=DATE:{}", name, date.format(DATE))
+    } else {
+        format!(
+            "{}:{}",
+            name,
+            date.with_timezone(&Utc).format(DATE_TIME_UTC)
+        )
+    }
+}
+
+// "20240308T020000Z", "20240308T090000" in local time, or "20240308".
+// a TZID parameter is not resolved, the time is taken as local time.
+fn parse_date_time(params: &str, value: &str) -> Result<DateTime<Local>, String> {
+    let invalid = || tr!("ical_invalid_date", date = format!("{:?}", value));
+    if let Ok(date) = NaiveDateTime::parse_from_str(value, DATE_TIME_UTC) {
+        return Ok(Utc.from_utc_datetime(&date).with_timezone(&Local));
+    }
+    let date = if params.contains("VALUE=DATE") || !value.contains('T') {
+        NaiveDate::parse_from_str(value, DATE)
+            .map_err(|_| invalid())?
+            .and_time(NaiveTime::MIN)
+    } else {
+        NaiveDateTime::parse_from_str(value, DATE_TIME).map_err(|_| invalid())?
+    };
+    Local
+        .from_local_datetime(&date)
+        .earliest()
+        .ok_or_else(invalid)
+}
+
+// "NAME;PARAM=x:value" -> (NAME, ";PARAM=x", value), colons in quoted params are skipped
+fn parse_line(line: &str) -> Option<(String, String, String)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find(|(_, c)| {
+        if *c == '"' {
+            quoted = !quoted;
+        }
+        *c == ':' && !quoted
+    })?;
+    let (head, value) = (&line[..colon.0], &line[colon.0 + 1..]);
+    let (name, params) = match head.find(';') {
+        Some(at) => (&head[..at], &head[at..]),
+        None => (head, ""),
+    };
+    Some((
+        name.to_uppercase(),
+        params.to_uppercase(),
+        value.to_string(),
+    ))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n') | Some('N') => out.push('\n'),
+                Some(c) => out.push(c),
+                None => {}
+            },
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// split a list value at the commas which are not escaped
+fn split_list(value: &str) -> Vec<String> {
+    let mut items = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+    for (idx, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ',' => {
+                items.push(unescape(&value[start..idx]));
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(unescape(&value[start..]));
+    items.retain(|x| !x.is_empty());
+    items
+}
+
+// split lines longer than LINE_WIDTH bytes, continuation lines start with a space
+fn fold(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > LINE_WIDTH {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out
+}
+
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for line in content.lines() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ if line.is_empty() => {}
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> Option<DateTime<Local>> {
+        Local.with_ymd_and_hms(2024, 3, day, hour, 0, 0).earliest()
+    }
+
+    #[test]
+    fn round_trip() {
+        let workflow = Workflow::default();
+        let mut list = TodoList::new();
+        let long = "a long title, with; special \\ characters and more words to fold the line";
+        for title in ["release #work #ops", long, "water plants", "done"] {
+            list.add(None, Task::new(title)).unwrap();
+        }
+        list.items[0].due = at(8, 9);
+        list.items[0].priority = Priority::High;
+        list.items[0].assignee = Some(String::from("alice"));
+        list.items[0].blocked_by = vec![list.items[3].id];
+        list.items[1].priority = Priority::Low;
+        list.items[1].due = at(9, 0);
+        list.items[2].recurrence = Some(String::from("FREQ=WEEKLY;BYDAY=MO"));
+        for task in list.items.iter_mut() {
+            task.state = String::from("todo");
+            task.created = at(1, 8);
+        }
+        list.items[0].state = String::from("review");
+        list.items[2].created = None;
+        list.items[3] = workflow.set_state(&list.items[3], "done");
+        list.items[3].done_at = at(2, 17);
+
+        let ics = export_ics(&list, &workflow);
+        assert!(ics.lines().all(|x| x.len() <= LINE_WIDTH + 1), "{}", ics);
+        assert!(ics.contains("CATEGORIES:work,ops\r\n"));
+        assert!(ics.contains("DUE;VALUE=DATE:20240309\r\n"));
+        let tasks = import_ics(&ics, &workflow).unwrap();
+        assert_eq!(tasks, list.items);
+    }
+
+    #[test]
+    fn import_from_other_apps() {
+        let workflow = Workflow::default();
+        let ics = [
+            "BEGIN:VCALENDAR",
+            "BEGIN:VEVENT",
+            "UID:event-1",
+            "SUMMARY:not a task",
+            "END:VEVENT",
+            "BEGIN:VTODO",
+            "UID:abc-123",
+            "SUMMARY:call bob",
+            "STATUS:IN-PROCESS",
+            "PRIORITY:3",
+            "CATEGORIES:phone,Work Stuff",
+            "DUE;TZID=Europe/Berlin:20240308T090000",
+            "END:VTODO",
+            "END:VCALENDAR",
+        ]
+        .join("\r\n");
+        let tasks = import_ics(&ics, &workflow).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "call bob #phone #Work-Stuff");
+        assert_eq!(tasks[0].state, "doing");
+        assert_eq!(tasks[0].priority, Priority::High);
+        assert_eq!(tasks[0].due, at(8, 9));
+        // the same UID gives the same id
+        assert_eq!(tasks[0].id, import_ics(&ics, &workflow).unwrap()[0].id);
+    }
+}
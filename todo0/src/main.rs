@@ -1,41 +1,106 @@
+mod batch;
 mod cmd;
+mod config;
+mod crypt;
+mod date;
+mod deps;
 mod file;
 mod help;
+mod hook;
+mod i18n;
+mod ical;
+mod markdown;
+mod migrate;
+mod org;
+mod plugin;
+mod scan;
+mod shell;
+mod snapshot;
+mod stats;
+mod template;
+mod term;
 mod todo;
+mod track;
+mod urgency;
+#[cfg(feature = "plugins")]
+mod wasm;
+mod watch;
+mod workflow;
 
+use batch::*;
 use cmd::*;
+use config::*;
+use crypt::*;
 use file::*;
 use help::*;
+use i18n::tr;
+use std::io::Read;
+use std::path::Path;
+use std::time::SystemTime;
 use std::{fs, process::exit};
 use todo::TodoList;
 
-use crate::todo::FormatOptions;
-
 fn main() {
-    let file_path = get_file_path();
-    let mut todo_list = load_from_file(&file_path)
-        .or::<()>(Ok(TodoList::new()))
-        .unwrap();
+    let config = match load_config(&get_config_path()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(1);
+        }
+    };
+    i18n::init(config.locale.as_deref());
+    let file_path = config.file_path();
+    // an encrypted file stays encrypted until "decrypt" is run
+    let mut secret = if is_encrypted_file(&file_path) {
+        Some(load_secret(&config))
+    } else {
+        None
+    };
+    let mut todo_list = match load_from_file(&file_path, secret.as_ref()) {
+        Ok(todo_list) => todo_list,
+        Err(err) => {
+            eprintln!("{}", err);
+            exit(1);
+        }
+    };
+
+    // another process may save the file while a command runs
+    let base = todo_list.clone();
+    let loaded_at = watch::modified_time(&file_path);
 
     let args: Vec<String> = std::env::args().collect();
     let args = &args[1..];
+    if args.first().is_some_and(|x| x == "list") && args.contains(&String::from("--follow")) {
+        let args: Vec<_> = args[1..]
+            .iter()
+            .filter(|x| *x != "--follow")
+            .cloned()
+            .collect();
+        if let Err(err) = cmd_follow(&file_path, secret.as_ref(), &config, &args) {
+            eprintln!("{}", err);
+            exit(1);
+        }
+    }
     let is_cmd_list = args.len() > 0 && args[0] == "list";
     if args.len() == 0 || is_cmd_list {
         if todo_list.items.len() == 0 {
-            eprintln!(
-                "There are no tasks. {}",
-                if is_cmd_list {
-                    String::new()
-                } else {
-                    short_help()
-                }
-            );
+            match is_cmd_list {
+                true => eprintln!("{}", tr!("no_tasks")),
+                false => eprintln!("{} {}", tr!("no_tasks"), short_help()),
+            }
             exit(1);
         } else {
-            println!("{}", todo_list.format(FormatOptions { use_color: true }));
+            let list_args = if is_cmd_list { &args[1..] } else { args };
+            if let Err(err) = cmd_list(&todo_list, &config, list_args) {
+                eprintln!("{}", err);
+                exit(1);
+            }
             exit(0);
         }
     }
+    // the post hooks run once the changes are saved, every exit before that
+    // drops them, so they never report a change which is not on disk
+    hook::defer_post_hooks();
     match args[0].as_str() {
         "help" => {
             print_help();
@@ -45,22 +110,281 @@ fn main() {
             print_help();
             exit(0);
         }
+        // only remove the tasks and keep the rest of a markdown or org file
+        "reset" if is_document_file(&file_path) => {
+            todo_list.items.clear();
+            eprintln!("{}", tr!("all_deleted"));
+        }
         "reset" => {
             if let Ok(_) = fs::remove_file(&file_path) {
-                eprintln!("deleted all tasks");
+                eprintln!("{}", tr!("all_deleted"));
+            }
+            exit(0);
+        }
+        "batch" | "-c" => {
+            let args = if args[0] == "-c" { args } else { &args[1..] };
+            match run_batch(&mut todo_list, &config, &file_path, secret.as_ref(), args) {
+                Ok(true) => {
+                    let merged = merge_external(&file_path, &secret, loaded_at, &base, todo_list);
+                    todo_list = merged.unwrap_or_else(|err| {
+                        eprintln!("{}", err);
+                        exit(1);
+                    });
+                }
+                Ok(false) => exit(0),
+                Err(err) => {
+                    eprintln!("{}\n{}", err, tr!("nothing_saved"));
+                    exit(1);
+                }
+            }
+        }
+        "shell" => {
+            if let Err(err) = shell::run_shell(&mut todo_list, &config, &file_path, &secret) {
+                eprintln!("{}", err);
+                exit(1);
             }
             exit(0);
         }
-        "add" => cmd_add(&mut todo_list, &args[1..]).unwrap(),
-        "edit" => cmd_edit(&mut todo_list, &args[1..]).unwrap(),
-        "del" => cmd_del(&mut todo_list, &args[1..]).unwrap(),
-        "delete" => cmd_del(&mut todo_list, &args[1..]).unwrap(),
-        "check" => cmd_check(&mut todo_list, &args[1..]).unwrap(),
-        "uncheck" => cmd_uncheck(&mut todo_list, &args[1..]).unwrap(),
+        "snapshots" | "diff" => {
+            let res = match args[0].as_str() {
+                "snapshots" => cmd_snapshots(&file_path, secret.as_ref(), &args[1..]),
+                _ => cmd_diff(&todo_list, &file_path, secret.as_ref(), &args[1..]),
+            };
+            if let Err(err) = res {
+                eprintln!("{}", err);
+                exit(1);
+            }
+            exit(0);
+        }
+        "restore" => {
+            if let Err(err) = cmd_restore(&mut todo_list, &file_path, secret.as_ref(), &args[1..]) {
+                eprintln!("{}", err);
+                exit(1);
+            }
+        }
+        "encrypt" => {
+            if is_document_file(&file_path) {
+                eprintln!("{}", tr!("document_not_encryptable"));
+                exit(1);
+            }
+            if secret.is_some() {
+                eprintln!("{}", tr!("already_encrypted"));
+                exit(1);
+            }
+            secret = Some(load_secret(&config));
+            let res = snapshot::reencrypt_snapshots(&file_path, None, secret.as_ref())
+                .and_then(|_| reencrypt_backups(&file_path, None, secret.as_ref()))
+                .and_then(|_| template::reencrypt_templates(&file_path, None, secret.as_ref()));
+            if let Err(err) = res {
+                eprintln!("{}", err);
+                exit(1);
+            }
+            println!("{}", tr!("encrypted", file = format!("{:?}", file_path)));
+        }
+        "decrypt" => {
+            if secret.is_none() {
+                eprintln!("{}", tr!("not_encrypted"));
+                exit(1);
+            }
+            let res = snapshot::reencrypt_snapshots(&file_path, secret.as_ref(), None)
+                .and_then(|_| reencrypt_backups(&file_path, secret.as_ref(), None))
+                .and_then(|_| template::reencrypt_templates(&file_path, secret.as_ref(), None));
+            if let Err(err) = res {
+                eprintln!("{}", err);
+                exit(1);
+            }
+            secret = None;
+            println!("{}", tr!("decrypted", file = format!("{:?}", file_path)));
+        }
+        _ => match run_command(&mut todo_list, &config, &file_path, secret.as_ref(), args) {
+            Ok(true) => {
+                let merged = merge_external(&file_path, &secret, loaded_at, &base, todo_list);
+                todo_list = merged.unwrap_or_else(|err| {
+                    eprintln!("{}", err);
+                    exit(1);
+                });
+            }
+            Ok(false) => exit(0),
+            Err(err) => {
+                eprintln!("{}", err);
+                exit(1);
+            }
+        },
+    }
+    if let Err(err) = save_list(&file_path, &todo_list, secret.as_ref(), &config) {
+        eprintln!("{}", err);
+        exit(1);
+    }
+    hook::run_deferred_hooks();
+}
+
+// save the list and take a snapshot of it, so that every snapshot was on disk.
+// a failed snapshot only warns.
+fn save_list(
+    file_path: &Path,
+    todo_list: &TodoList,
+    secret: Option<&Secret>,
+    config: &Config,
+) -> Result<(), String> {
+    save_to_file(file_path, todo_list.clone(), secret)?;
+    let now = chrono::Local::now();
+    let snapshot = snapshot::take_snapshot(file_path, todo_list, secret, &config.snapshots, now);
+    if let Err(err) = snapshot {
+        eprintln!("{}", tr!("snapshot_failed", error = err));
+    }
+    Ok(())
+}
+
+// run a command on the list, returns whether the list needs to be saved
+fn run_command(
+    todo_list: &mut TodoList,
+    config: &Config,
+    file_path: &Path,
+    secret: Option<&Secret>,
+    args: &[String],
+) -> Result<bool, String> {
+    check_file_command(config, args)?;
+    match args[0].as_str() {
+        "list" => {
+            cmd_list(todo_list, config, &args[1..])?;
+            return Ok(false);
+        }
+        "add" => cmd_add(todo_list, config, &args[1..])?,
+        "edit" => cmd_edit(todo_list, config, &args[1..])?,
+        "del" => cmd_del(todo_list, config, &args[1..])?,
+        "delete" => cmd_del(todo_list, config, &args[1..])?,
+        "check" => cmd_check(todo_list, config, &args[1..])?,
+        "uncheck" => cmd_uncheck(todo_list, config, &args[1..])?,
+        "assign" => cmd_assign(todo_list, config, &args[1..])?,
+        "unassign" => cmd_unassign(todo_list, config, &args[1..])?,
+        "mv-state" => cmd_mv_state(todo_list, config, &args[1..])?,
+        "board" => {
+            cmd_board(todo_list, config)?;
+            return Ok(false);
+        }
+        "start" => cmd_start(todo_list, &args[1..])?,
+        "stop" => cmd_stop(todo_list, &args[1..])?,
+        "block" => cmd_block(todo_list, &args[1..])?,
+        "unblock" => cmd_unblock(todo_list, &args[1..])?,
+        "ready" => {
+            cmd_ready(todo_list)?;
+            return Ok(false);
+        }
+        "next" => {
+            cmd_next(todo_list, config)?;
+            return Ok(false);
+        }
+        "graph" => {
+            cmd_graph(todo_list)?;
+            return Ok(false);
+        }
+        "prio" => cmd_prio(todo_list, config, &args[1..])?,
+        "stats" => {
+            cmd_stats(todo_list, config, &args[1..])?;
+            return Ok(false);
+        }
+        "template" => {
+            cmd_template(todo_list, config, file_path, secret, &args[1..])?;
+            // only "apply" changes the list, the others read or write the templates
+            if args.get(1).is_none_or(|x| x != "apply") {
+                return Ok(false);
+            }
+        }
+        "export" => {
+            cmd_export(todo_list, config, &args[1..])?;
+            return Ok(false);
+        }
+        "import" => cmd_import(todo_list, config, &args[1..])?,
+        "scan" => {
+            cmd_scan(config, &args[1..])?;
+            return Ok(false);
+        }
+        "report" => {
+            cmd_report(todo_list, &args[1..])?;
+            return Ok(false);
+        }
+        "plugins" => {
+            cmd_plugins(config)?;
+            return Ok(false);
+        }
         _ => {
-            eprintln!("Unknow command. {}", short_help());
+            if !plugin::run_plugin_command(todo_list, config, args)? {
+                return Err(tr!("unknown_command", help = short_help()));
+            }
+        }
+    }
+    Ok(true)
+}
+
+// run the commands of the script from stdin or "-c", the list is only saved
+// when all of them succeed. the post hooks are deferred until the save, and
+// commands which write other files are not available, so that a failed
+// script leaves no trace.
+fn run_batch(
+    todo_list: &mut TodoList,
+    config: &Config,
+    file_path: &Path,
+    secret: Option<&Secret>,
+    args: &[String],
+) -> Result<bool, String> {
+    let script = match args {
+        [] => {
+            let mut script = String::new();
+            std::io::stdin()
+                .read_to_string(&mut script)
+                .map_err(|err| tr!("read_script_failed", error = err))?;
+            script
+        }
+        [flag, script] if flag == "-c" => script.clone(),
+        _ => return Err(tr!("invalid_arguments", help = short_help())),
+    };
+    let mut modified = false;
+    for (line, args) in parse_script(&script)? {
+        let res = match args[0].as_str() {
+            "batch" | "-c" | "help" | "reset" | "encrypt" | "decrypt" | "snapshots" | "restore"
+            | "diff" | "shell" | "scan" => Err(tr!("not_in_batch")),
+            "template" if args.get(1).is_some_and(|x| x == "save" || x == "del") => {
+                Err(tr!("not_in_batch"))
+            }
+            _ => run_command(todo_list, config, file_path, secret, &args),
+        };
+        let command = args.join(" ");
+        modified |=
+            res.map_err(|err| tr!("script_error", line = line, command = command, error = err))?;
+    }
+    Ok(modified)
+}
+
+// when another process saved the file since it was loaded, e.g. a sync tool or
+// a second terminal, merge its changes instead of overwriting them. the task
+// ids of markdown and org files depend on the lines, so they are not merged
+// and not overwritten either. a file which can not be read, e.g. of a newer
+// version, is not overwritten.
+fn merge_external(
+    file_path: &Path,
+    secret: &Option<Secret>,
+    loaded_at: Option<SystemTime>,
+    base: &TodoList,
+    todo_list: TodoList,
+) -> Result<TodoList, String> {
+    if watch::modified_time(file_path) == loaded_at {
+        return Ok(todo_list);
+    }
+    if is_document_file(file_path) {
+        return Err(tr!("document_changed", file = format!("{:?}", file_path)));
+    }
+    let theirs =
+        load_from_file(file_path, secret.as_ref()).map_err(|err| tr!("not_merged", error = err))?;
+    eprintln!("{}", tr!("merged", file = format!("{:?}", file_path)));
+    Ok(watch::merge(base, &todo_list, &theirs))
+}
+
+fn load_secret(config: &Config) -> Secret {
+    match Secret::load(config.keyfile.as_deref()) {
+        Ok(secret) => secret,
+        Err(err) => {
+            eprintln!("{}", err);
             exit(1);
         }
     }
-    save_to_file(&file_path, todo_list).unwrap();
 }
@@ -0,0 +1,300 @@
+use crate::todo::*;
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+// a checklist item in a markdown file, e.g. "  - [x] hello foo"
+// the line is split so that it can be written back with only the changed parts
+#[derive(Debug)]
+struct Item {
+    line: usize,
+    indent: usize,
+    // "  - [", the indent, the bullet and the opening bracket
+    prefix: String,
+    checked: bool,
+    // "] " up to the text
+    middle: String,
+    text: String,
+    id: TaskId,
+}
+
+pub fn is_markdown_file(file_path: &std::path::Path) -> bool {
+    matches!(
+        file_path.extension().and_then(|x| x.to_str()),
+        Some("md") | Some("markdown")
+    )
+}
+
+// read the checklist items of the markdown, nested items become tasks too.
+// everything else in the file is ignored here and kept by save_markdown.
+pub fn load_markdown(content: &str) -> TodoList {
+    let mut list = TodoList::new();
+    list.items = parse_items(content)
+        .into_iter()
+        .map(|item| Task {
+            id: item.id,
+            title: item.text,
+            done: item.checked,
+            ..Default::default()
+        })
+        .collect();
+    list
+}
+
+// write the list back into the markdown it was loaded from. unchanged items
+// and all other lines are kept as they are, changed items only get their
+// checkbox or text replaced, deleted items are removed and new items are
+// inserted after the item before them in the list. only the title and the
+// done state are stored in markdown.
+pub fn save_markdown(content: &str, list: &TodoList) -> String {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let items = parse_items(content);
+    let by_id: HashMap<TaskId, &Item> = items.iter().map(|x| (x.id, x)).collect();
+
+    let mut replaced: HashMap<usize, Option<String>> = HashMap::new();
+    // new lines by the item line they come after, None is before the first item
+    let mut inserted: HashMap<Option<usize>, Vec<String>> = HashMap::new();
+    let mut prev: Option<&Item> = None;
+    for task in list.items.iter() {
+        match by_id.get(&task.id) {
+            Some(item) => {
+                if item.text != task.title || item.checked != task.done {
+                    replaced.insert(item.line, Some(render(item, task)));
+                }
+                prev = Some(item);
+            }
+            None => {
+                let template = prev.or(items.first());
+                let line = match template {
+                    Some(item) => render(item, task),
+                    None => render_new(task),
+                };
+                let after = prev.map(|x| block_end(&lines, x));
+                inserted.entry(after).or_default().push(line);
+            }
+        }
+    }
+    let kept: Vec<TaskId> = list.items.iter().map(|x| x.id).collect();
+    for item in items.iter().filter(|x| !kept.contains(&x.id)) {
+        replaced.insert(item.line, None);
+    }
+
+    let ending = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut out = String::new();
+    let push = |out: &mut String, line: &str| {
+        out.push_str(line);
+        out.push_str(ending);
+    };
+    let first_line = items.first().map(|x| x.line);
+    for (idx, line) in lines.iter().enumerate() {
+        if Some(idx) == first_line {
+            for new_line in inserted.get(&None).into_iter().flatten() {
+                push(&mut out, new_line);
+            }
+        }
+        match replaced.get(&idx) {
+            None => out.push_str(line),
+            Some(None) => {}
+            Some(Some(new_line)) => {
+                let (_, eol) = split_eol(line);
+                out.push_str(new_line);
+                out.push_str(eol);
+            }
+        }
+        for new_line in inserted.get(&Some(idx)).into_iter().flatten() {
+            if !out.is_empty() && !out.ends_with('\n') {
+                out.push_str(ending);
+            }
+            push(&mut out, new_line);
+        }
+    }
+    if first_line.is_none() {
+        for new_line in inserted.get(&None).into_iter().flatten() {
+            if !out.is_empty() && !out.ends_with('\n') {
+                out.push_str(ending);
+            }
+            push(&mut out, new_line);
+        }
+    }
+    out
+}
+
+fn parse_items(content: &str) -> Vec<Item> {
+    let mut items = vec![];
+    let mut in_code = false;
+    for (idx, line) in content.split_inclusive('\n').enumerate() {
+        let (line, _) = split_eol(line);
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        if let Some(item) = parse_item(idx, line) {
+            items.push(item);
+        }
+    }
+    items
+}
+
+// "- [ ] text", "* [x] text", "1. [ ] text", with any indent
+fn parse_item(idx: usize, line: &str) -> Option<Item> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    let bullet_len = if rest.starts_with(['-', '*', '+']) {
+        1
+    } else {
+        let digits = rest.chars().take_while(|x| x.is_ascii_digit()).count();
+        match rest[digits..].chars().next() {
+            Some('.') | Some(')') if digits > 0 => digits + 1,
+            _ => return None,
+        }
+    };
+    let after_bullet = &rest[bullet_len..];
+    let spaces = after_bullet.len() - after_bullet.trim_start().len();
+    if spaces == 0 {
+        return None;
+    }
+    let checkbox = &after_bullet[spaces..];
+    let checked = match checkbox.get(..3) {
+        Some("[ ]") => false,
+        Some("[x]") | Some("[X]") => true,
+        _ => return None,
+    };
+    let after_box = &checkbox[3..];
+    let text = after_box.trim_start();
+    if text.is_empty() || after_box.len() == text.len() {
+        return None;
+    }
+    let prefix_len = indent + bullet_len + spaces + 1;
+    let middle_len = 1 + after_box.len() - text.len();
+
+    let mut hasher = DefaultHasher::new();
+    idx.hash(&mut hasher);
+    line.hash(&mut hasher);
+    Some(Item {
+        line: idx,
+        indent,
+        prefix: line[..prefix_len].to_string(),
+        checked,
+        middle: line[prefix_len + 1..prefix_len + 1 + middle_len].to_string(),
+        text: text.to_string(),
+        id: TaskId(hasher.finish()),
+    })
+}
+
+// the last line of the item and the lines nested under it
+fn block_end(lines: &[&str], item: &Item) -> usize {
+    let mut end = item.line;
+    for (idx, line) in lines.iter().enumerate().skip(item.line + 1) {
+        let (line, _) = split_eol(line);
+        let indent = line.len() - line.trim_start().len();
+        if line.trim().is_empty() || indent <= item.indent {
+            break;
+        }
+        end = idx;
+    }
+    end
+}
+
+fn render(item: &Item, task: &Task) -> String {
+    let mark = if task.done { 'x' } else { ' ' };
+    format!("{}{}{}{}", item.prefix, mark, item.middle, task.title)
+}
+
+fn render_new(task: &Task) -> String {
+    let mark = if task.done { 'x' } else { ' ' };
+    format!("- [{}] {}", mark, task.title)
+}
+
+fn split_eol(line: &str) -> (&str, &str) {
+    let content = line.trim_end_matches(['\n', '\r']);
+    (content, &line[content.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "# Project
+
+Some notes about the project.
+
+- [ ] write docs
+  * [x] intro
+  * [ ] usage
+- [x] fix login
+
+```
+- [ ] not a task
+```
+
+More prose.
+";
+
+    #[test]
+    fn load_items() {
+        let list = load_markdown(CONTENT);
+        let tasks: Vec<_> = list
+            .items
+            .iter()
+            .map(|x| (x.title.as_str(), x.done))
+            .collect();
+        assert_eq!(
+            tasks,
+            vec![
+                ("write docs", false),
+                ("intro", true),
+                ("usage", false),
+                ("fix login", true)
+            ]
+        );
+    }
+
+    #[test]
+    fn save_minimal_diff() {
+        // nothing changed
+        let list = load_markdown(CONTENT);
+        assert_eq!(save_markdown(CONTENT, &list), CONTENT);
+
+        let mut list = load_markdown(CONTENT);
+        list.items[2].done = true;
+        list.items[3].title = String::from("fix login page");
+        list.del(Some(Position::AtIndex(1))).unwrap();
+        list.add(Some(Position::AtIndex(1)), Task::new("release"))
+            .unwrap();
+        list.add(None, Task::new("celebrate")).unwrap();
+        let expected = "# Project
+
+Some notes about the project.
+
+- [ ] write docs
+  * [x] usage
+- [ ] release
+- [x] fix login page
+- [ ] celebrate
+
+```
+- [ ] not a task
+```
+
+More prose.
+";
+        assert_eq!(save_markdown(CONTENT, &list), expected);
+    }
+
+    #[test]
+    fn save_into_empty_file() {
+        let mut list = load_markdown("# Todo\n");
+        list.add(None, Task::new("A")).unwrap();
+        assert_eq!(save_markdown("# Todo\n", &list), "# Todo\n- [ ] A\n");
+        assert_eq!(save_markdown("", &list), "- [ ] A\n");
+    }
+}
@@ -0,0 +1,97 @@
+use crate::i18n::tr;
+
+use serde_json::Value;
+
+// the version written by save_to_file, bump it together with a new migration
+pub const CURRENT_VERSION: u64 = 9;
+
+type Migration = fn(Value) -> Result<Value, String>;
+
+// MIGRATIONS[n] upgrades a document from version n to version n + 1
+const MIGRATIONS: [Migration; CURRENT_VERSION as usize] = [
+    v0_to_v1, v1_to_v2, v2_to_v3, v3_to_v4, v4_to_v5, v5_to_v6, v6_to_v7, v7_to_v8, v8_to_v9,
+];
+
+// files written before versioning have no version field
+pub fn get_version(doc: &Value) -> Result<u64, String> {
+    match doc.get("version") {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .ok_or(tr!("invalid_version", version = version)),
+    }
+}
+
+// upgrade the document to CURRENT_VERSION, one migration at a time
+pub fn migrate(mut doc: Value) -> Result<Value, String> {
+    let version = get_version(&doc)?;
+    if version > CURRENT_VERSION {
+        return Err(tr!(
+            "newer_version",
+            version = version,
+            supported = CURRENT_VERSION
+        ));
+    }
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        doc = migration(doc).map_err(|err| tr!("migrate_failed", version = from, error = err))?;
+        doc["version"] = Value::from(from as u64 + 1);
+    }
+    Ok(doc)
+}
+
+// v0: { "items": [...] }
+// v1: { "version": 1, "items": [...] }
+fn v0_to_v1(doc: Value) -> Result<Value, String> {
+    if !doc.is_object() {
+        return Err(tr!("expected_object"));
+    }
+    Ok(doc)
+}
+
+// v2: tasks may have "intervals": [{ "start": "...", "end": "..." }]
+fn v1_to_v2(doc: Value) -> Result<Value, String> {
+    Ok(doc)
+}
+
+// v3: tasks may have "blocked_by": [<task id>, ...]
+fn v2_to_v3(doc: Value) -> Result<Value, String> {
+    Ok(doc)
+}
+
+// v4: tasks may have "due": "<rfc 3339 date time>"
+fn v3_to_v4(doc: Value) -> Result<Value, String> {
+    Ok(doc)
+}
+
+// v5: tasks have a workflow "state", done tasks start as "done" and the others as "todo"
+fn v4_to_v5(mut doc: Value) -> Result<Value, String> {
+    let items = doc["items"].as_array_mut().ok_or(tr!("expected_items"))?;
+    for item in items.iter_mut() {
+        let state = match item["done"].as_bool() {
+            Some(true) => "done",
+            _ => "todo",
+        };
+        item["state"] = Value::from(state);
+    }
+    Ok(doc)
+}
+
+// v6: tasks may have an "assignee"
+fn v5_to_v6(doc: Value) -> Result<Value, String> {
+    Ok(doc)
+}
+
+// v7: tasks may have "priority", "created" and "done_at"
+fn v6_to_v7(doc: Value) -> Result<Value, String> {
+    Ok(doc)
+}
+
+// v8: tasks may have a "source": { "file": "...", "line": <n> }
+fn v7_to_v8(doc: Value) -> Result<Value, String> {
+    Ok(doc)
+}
+
+// v9: tasks may have a "recurrence"
+fn v8_to_v9(doc: Value) -> Result<Value, String> {
+    Ok(doc)
+}
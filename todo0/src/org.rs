@@ -0,0 +1,756 @@
+use crate::term::width;
+use crate::todo::*;
+use crate::workflow::Workflow;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+// the property export writes the task id to, so that an import updates the tasks
+const ID_PROPERTY: &str = "TODO0_ID";
+
+// the TODO keywords of a file, set with lines like "#+TODO: TODO DOING | DONE"
+#[derive(Debug, PartialEq)]
+struct Keywords {
+    todo: Vec<String>,
+    done: Vec<String>,
+}
+
+// a heading with a TODO keyword, e.g. "** DONE [#A] fix login :work:" and the
+// planning line and properties below it
+#[derive(Debug)]
+struct Item {
+    line: usize,
+    level: usize,
+    // the column the tags start at, kept when the heading is written back
+    tags_col: Option<usize>,
+    planning: Option<Planning>,
+    // the last line before the next heading, and before the next heading of
+    // the same or a higher level
+    section_end: usize,
+    subtree_end: usize,
+    // the TODO0_ID property, the id is a hash of the line without it
+    explicit_id: Option<TaskId>,
+    task: Task,
+}
+
+// "DEADLINE: <2024-03-05 Tue> SCHEDULED: <2024-03-01 Fri>", the timestamps
+// are kept as written unless the task changes
+#[derive(Debug, Default)]
+struct Planning {
+    line: usize,
+    indent: String,
+    closed: Option<String>,
+    deadline: Option<String>,
+    scheduled: Option<String>,
+}
+
+pub fn is_org_file(file_path: &std::path::Path) -> bool {
+    file_path.extension().and_then(|x| x.to_str()) == Some("org")
+}
+
+// read the headings with a TODO keyword as tasks, the keyword becomes the
+// state. the deadline, or the scheduled date without one, is the due date.
+// everything else in the file is ignored here and kept by save_org.
+pub fn load_org(content: &str) -> TodoList {
+    let mut list = TodoList::new();
+    list.items = parse(content).1.into_iter().map(|x| x.task).collect();
+    list
+}
+
+// write the list back into the org file it was loaded from, like
+// markdown::save_markdown: only the headings and planning lines of changed
+// tasks are rewritten, deleted tasks are removed with their subtree and new
+// tasks are inserted after the subtree of the task before them. the title,
+// state, priority, tags, due date, recurrence and closing time are stored.
+pub fn save_org(content: &str, list: &TodoList) -> String {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let (keywords, items) = parse(content);
+    let by_id: HashMap<TaskId, &Item> = items.iter().map(|x| (x.task.id, x)).collect();
+
+    let mut replaced: HashMap<usize, Option<String>> = HashMap::new();
+    // new lines by the line they come after, None is before the first task
+    let mut inserted: HashMap<Option<usize>, Vec<String>> = HashMap::new();
+    let mut prev: Option<&Item> = None;
+    for task in list.items.iter() {
+        match by_id.get(&task.id) {
+            Some(item) => {
+                let old = &item.task;
+                let heading_changed = (&old.title, old.done, &old.state, old.priority)
+                    != (&task.title, task.done, &task.state, task.priority);
+                if heading_changed {
+                    let heading = render_heading(item.level, &keywords, task, item.tags_col);
+                    replaced.insert(item.line, Some(heading));
+                }
+                let planning_changed = (old.due, old.done_at, &old.recurrence)
+                    != (task.due, task.done_at, &task.recurrence);
+                if planning_changed {
+                    let planning = render_planning(item.planning.as_ref(), old, task);
+                    match &item.planning {
+                        Some(x) => {
+                            replaced.insert(x.line, planning);
+                        }
+                        None => inserted
+                            .entry(Some(item.line))
+                            .or_default()
+                            .extend(planning),
+                    }
+                }
+                prev = Some(item);
+            }
+            None => {
+                let level = prev.or(items.first()).map_or(1, |x| x.level);
+                let mut new_lines = vec![render_heading(level, &keywords, task, None)];
+                new_lines.extend(render_planning(None, &Task::default(), task));
+                let after = prev.map(|x| x.subtree_end);
+                inserted.entry(after).or_default().extend(new_lines);
+            }
+        }
+    }
+    let kept: Vec<TaskId> = list.items.iter().map(|x| x.id).collect();
+    let is_kept = |item: &&Item| kept.contains(&item.task.id);
+    for item in items.iter().filter(|x| !is_kept(x)) {
+        // the headings below the task go with it, except the kept tasks and
+        // their subtrees
+        for line in item.line..=item.subtree_end {
+            let in_kept = items
+                .iter()
+                .filter(is_kept)
+                .any(|x| x.line > item.line && x.line <= line && line <= x.subtree_end);
+            if !in_kept {
+                replaced.insert(line, None);
+            }
+        }
+    }
+
+    let ending = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut out = String::new();
+    let push = |out: &mut String, line: &str| {
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push_str(ending);
+        }
+        out.push_str(line);
+        out.push_str(ending);
+    };
+    let first_line = items.first().map(|x| x.line);
+    for (idx, line) in lines.iter().enumerate() {
+        if Some(idx) == first_line {
+            for new_line in inserted.get(&None).into_iter().flatten() {
+                push(&mut out, new_line);
+            }
+        }
+        match replaced.get(&idx) {
+            None => out.push_str(line),
+            Some(None) => {}
+            Some(Some(new_line)) => {
+                let (_, eol) = split_eol(line);
+                out.push_str(new_line);
+                out.push_str(eol);
+            }
+        }
+        for new_line in inserted.get(&Some(idx)).into_iter().flatten() {
+            push(&mut out, new_line);
+        }
+    }
+    if first_line.is_none() {
+        for new_line in inserted.get(&None).into_iter().flatten() {
+            push(&mut out, new_line);
+        }
+    }
+    out
+}
+
+// the tasks as an org document, with the workflow states as TODO keywords and
+// the task ids as properties
+pub fn export_org(list: &TodoList, workflow: &Workflow) -> String {
+    let keyword = |state: &str| state.to_uppercase().replace(char::is_whitespace, "_");
+    let keywords = Keywords {
+        todo: workflow.states[..workflow.states.len() - 1]
+            .iter()
+            .map(|x| keyword(x))
+            .collect(),
+        done: vec![keyword(workflow.final_state())],
+    };
+    let mut out = format!(
+        "#+TODO: {} | {}\n",
+        keywords.todo.join(" "),
+        keywords.done.join(" ")
+    );
+    for task in list.items.iter() {
+        let task = Task {
+            state: workflow.state_of(task).to_string(),
+            ..task.clone()
+        };
+        out.push_str(&render_heading(1, &keywords, &task, None));
+        out.push('\n');
+        if let Some(planning) = render_planning(None, &Task::default(), &task) {
+            out.push_str(&planning);
+            out.push('\n');
+        }
+        out.push_str(&format!(
+            ":PROPERTIES:\n:{}: {}\n:END:\n",
+            ID_PROPERTY, task.id.0
+        ));
+    }
+    out
+}
+
+// the tasks of an org file. tasks exported by todo0 keep their ids, the
+// others get new ones.
+pub fn import_org(content: &str, workflow: &Workflow) -> Vec<Task> {
+    parse(content)
+        .1
+        .into_iter()
+        .map(|item| {
+            let mut task = item.task;
+            task.id = item.explicit_id.unwrap_or_else(TaskId::new);
+            if !task.done && !workflow.states.contains(&task.state) {
+                task.state = workflow.initial().to_string();
+            }
+            if task.done {
+                task.state = workflow.final_state().to_string();
+            }
+            task
+        })
+        .collect()
+}
+
+// the states of the tasks which are not done the file can store, its TODO
+// keywords in lower case like the states of the loaded tasks
+pub fn open_states(content: &str) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let keywords = parse_keywords(&lines);
+    keywords.todo.iter().map(|x| x.to_lowercase()).collect()
+}
+
+// the task in the list with the fields of an org heading, the others like the
+// assignee and the tracked time are kept
+pub fn update_from_org(old: &Task, imported: Task) -> Task {
+    Task {
+        id: imported.id,
+        title: imported.title,
+        done: imported.done,
+        state: imported.state,
+        due: imported.due,
+        priority: imported.priority,
+        recurrence: imported.recurrence,
+        done_at: imported.done_at,
+        ..old.clone()
+    }
+}
+
+fn parse(content: &str) -> (Keywords, Vec<Item>) {
+    let lines: Vec<&str> = content
+        .split_inclusive('\n')
+        .map(|x| split_eol(x).0)
+        .collect();
+    let keywords = parse_keywords(&lines);
+    let mut items = vec![];
+    // the level of every heading, to find the end of the sections
+    let mut headings: Vec<(usize, usize)> = vec![];
+    let mut in_block = false;
+    for (idx, line) in lines.iter().enumerate() {
+        let lower = line.trim_start().to_lowercase();
+        if lower.starts_with("#+begin_") {
+            in_block = true;
+        } else if lower.starts_with("#+end_") {
+            in_block = false;
+        }
+        if in_block {
+            continue;
+        }
+        let level = heading_level(line);
+        if level == 0 {
+            continue;
+        }
+        headings.push((idx, level));
+        if let Some(item) = parse_item(&lines, idx, level, &keywords) {
+            items.push(item);
+        }
+    }
+    for item in items.iter_mut() {
+        let (start, item_level) = (item.line, item.level);
+        let next = |f: &dyn Fn(usize) -> bool| {
+            headings
+                .iter()
+                .find(|(line, level)| *line > start && f(*level))
+                .map_or(lines.len(), |(line, _)| *line)
+                - 1
+        };
+        item.section_end = next(&|_| true);
+        item.subtree_end = next(&|level| level <= item_level);
+    }
+    (keywords, items)
+}
+
+fn parse_keywords(lines: &[&str]) -> Keywords {
+    let mut keywords = Keywords {
+        todo: vec![],
+        done: vec![],
+    };
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !["#+TODO", "#+SEQ_TODO", "#+TYP_TODO"].contains(&name.to_uppercase().as_str()) {
+            continue;
+        }
+        // "TODO(t)" has a shortcut key, "DONE(d!)" a logging setting
+        let words: Vec<String> = value
+            .split_whitespace()
+            .map(|x| x.split('(').next().unwrap().to_string())
+            .collect();
+        match words.iter().position(|x| x == "|") {
+            Some(pos) => {
+                keywords.todo.extend_from_slice(&words[..pos]);
+                keywords.done.extend_from_slice(&words[pos + 1..]);
+            }
+            None if !words.is_empty() => {
+                keywords.todo.extend_from_slice(&words[..words.len() - 1]);
+                keywords.done.push(words[words.len() - 1].clone());
+            }
+            None => {}
+        }
+    }
+    if keywords.todo.is_empty() {
+        keywords.todo.push(String::from("TODO"));
+    }
+    if keywords.done.is_empty() {
+        keywords.done.push(String::from("DONE"));
+    }
+    keywords
+}
+
+fn heading_level(line: &str) -> usize {
+    let stars = line.chars().take_while(|x| *x == '*').count();
+    match line[stars..].chars().next() {
+        Some(' ') if stars > 0 => stars,
+        _ => 0,
+    }
+}
+
+fn parse_item(lines: &[&str], idx: usize, level: usize, keywords: &Keywords) -> Option<Item> {
+    let line = lines[idx];
+    let rest = line[level..].trim_start();
+    let (keyword, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+    let done = keywords.done.iter().any(|x| x == keyword);
+    if !done && !keywords.todo.iter().any(|x| x == keyword) {
+        return None;
+    }
+    let mut rest = rest.trim_start();
+    let mut priority = Priority::None;
+    if rest.starts_with("[#") && rest.get(3..4) == Some("]") {
+        priority = match &rest[2..3] {
+            "A" => Priority::High,
+            "B" => Priority::Medium,
+            "C" => Priority::Low,
+            _ => Priority::None,
+        };
+        rest = rest[4..].trim_start();
+    }
+    let rest = rest.trim_end();
+    let (text, tags, tags_col) = match rest.rsplit_once(char::is_whitespace) {
+        Some((text, tags)) if is_tags(tags) => {
+            let col = width(&line[..line.rfind(tags)?]);
+            (text.trim_end(), tags, Some(col))
+        }
+        _ if is_tags(rest) => ("", rest, None),
+        _ => (rest, "", None),
+    };
+    let mut title = text.to_string();
+    for tag in tags.split(':').filter(|x| !x.is_empty()) {
+        title = format!("{} #{}", title, tag).trim_start().to_string();
+    }
+    if title.is_empty() {
+        return None;
+    }
+
+    let mut next = idx + 1;
+    let planning = lines
+        .get(next)
+        .and_then(|x| parse_planning(next, x))
+        .inspect(|_| next += 1);
+    let mut explicit_id = None;
+    if lines.get(next).map(|x| x.trim()) == Some(":PROPERTIES:") {
+        for line in lines[next + 1..].iter().take_while(|x| x.trim() != ":END:") {
+            let property = line.trim().strip_prefix(&format!(":{}:", ID_PROPERTY));
+            if let Some(id) = property.and_then(|x| x.trim().parse().ok()) {
+                explicit_id = Some(TaskId(id));
+            }
+        }
+    }
+    let id = explicit_id.unwrap_or_else(|| {
+        let mut hasher = DefaultHasher::new();
+        idx.hash(&mut hasher);
+        line.hash(&mut hasher);
+        TaskId(hasher.finish())
+    });
+
+    let default = Planning::default();
+    let plan = planning.as_ref().unwrap_or(&default);
+    let due = plan
+        .deadline
+        .as_ref()
+        .or(plan.scheduled.as_ref())
+        .and_then(|x| parse_timestamp(x));
+    let task = Task {
+        id,
+        title,
+        done,
+        state: keyword.to_lowercase(),
+        due: due.as_ref().map(|x| x.0),
+        recurrence: due.and_then(|x| x.1),
+        priority,
+        done_at: plan
+            .closed
+            .as_ref()
+            .and_then(|x| parse_timestamp(x))
+            .map(|x| x.0),
+        ..Default::default()
+    };
+    Some(Item {
+        line: idx,
+        level,
+        tags_col,
+        planning,
+        section_end: idx,
+        subtree_end: idx,
+        explicit_id,
+        task,
+    })
+}
+
+fn is_tags(word: &str) -> bool {
+    word.len() > 2
+        && word.starts_with(':')
+        && word.ends_with(':')
+        && word[1..word.len() - 1].split(':').all(is_tag)
+}
+
+fn is_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || "_@#%".contains(c))
+}
+
+fn parse_planning(idx: usize, line: &str) -> Option<Planning> {
+    let mut planning = Planning {
+        line: idx,
+        indent: line[..line.len() - line.trim_start().len()].to_string(),
+        ..Default::default()
+    };
+    let mut rest = line.trim();
+    while !rest.is_empty() {
+        let (key, value) = rest.split_once(':')?;
+        let value = value.trim_start();
+        let close = match value.chars().next()? {
+            '<' => '>',
+            '[' => ']',
+            _ => return None,
+        };
+        let end = value.find(close)? + 1;
+        let timestamp = Some(value[..end].to_string());
+        match key {
+            "CLOSED" => planning.closed = timestamp,
+            "DEADLINE" => planning.deadline = timestamp,
+            "SCHEDULED" => planning.scheduled = timestamp,
+            _ => return None,
+        }
+        rest = value[end..].trim_start();
+    }
+    Some(planning)
+}
+
+// "<2024-03-05 Tue>", "<2024-03-05 Tue 10:00 +1w>" or "[2024-03-05 Tue 10:00]",
+// a repeater is returned as recurrence, e.g. "FREQ=WEEKLY"
+fn parse_timestamp(timestamp: &str) -> Option<(DateTime<Local>, Option<String>)> {
+    let inner = timestamp.get(1..timestamp.len() - 1)?;
+    let mut words = inner.split_whitespace();
+    let date = NaiveDate::parse_from_str(words.next()?, "%Y-%m-%d").ok()?;
+    let mut time = NaiveTime::MIN;
+    let mut recurrence = None;
+    for word in words {
+        // a time range like "10:00-11:00" starts at the first time
+        let start = word.split('-').next().unwrap();
+        if let Ok(x) = NaiveTime::parse_from_str(start, "%H:%M") {
+            time = x;
+        } else if let Some(repeater) = word.strip_prefix(['+', '.']) {
+            let repeater = repeater.trim_start_matches('+');
+            let (count, unit) = repeater.split_at(repeater.len().saturating_sub(1));
+            let freq = match unit {
+                "h" => "HOURLY",
+                "d" => "DAILY",
+                "w" => "WEEKLY",
+                "m" => "MONTHLY",
+                "y" => "YEARLY",
+                _ => continue,
+            };
+            recurrence = match count.parse::<u32>() {
+                Ok(1) => Some(format!("FREQ={}", freq)),
+                Ok(count) => Some(format!("FREQ={};INTERVAL={}", freq, count)),
+                Err(_) => None,
+            };
+        }
+    }
+    let date = Local.from_local_datetime(&date.and_time(time)).earliest()?;
+    Some((date, recurrence))
+}
+
+// a date at midnight is written without the time, like format_due shows it.
+// only a recurrence of one FREQ and INTERVAL can be written as repeater.
+fn format_timestamp(date: DateTime<Local>, active: bool, recurrence: Option<&str>) -> String {
+    let mut out = date.format("%Y-%m-%d %a").to_string();
+    if date.time() != NaiveTime::MIN {
+        out.push_str(&date.format(" %H:%M").to_string());
+    }
+    if let Some(repeater) = recurrence.and_then(format_repeater) {
+        out.push(' ');
+        out.push_str(&repeater);
+    }
+    match active {
+        true => format!("<{}>", out),
+        false => format!("[{}]", out),
+    }
+}
+
+fn format_repeater(recurrence: &str) -> Option<String> {
+    let mut unit = None;
+    let mut count = 1;
+    for part in recurrence.split(';') {
+        match part.split_once('=')? {
+            ("FREQ", "HOURLY") => unit = Some('h'),
+            ("FREQ", "DAILY") => unit = Some('d'),
+            ("FREQ", "WEEKLY") => unit = Some('w'),
+            ("FREQ", "MONTHLY") => unit = Some('m'),
+            ("FREQ", "YEARLY") => unit = Some('y'),
+            ("INTERVAL", value) => count = value.parse().ok()?,
+            _ => return None,
+        }
+    }
+    Some(format!("+{}{}", count, unit?))
+}
+
+// the #tags at the end of the title are written as org tags at the end of the
+// heading, other words like "#42" in "see #42 first" or "#follow-up" which is
+// not a valid org tag stay in the text
+fn render_heading(
+    level: usize,
+    keywords: &Keywords,
+    task: &Task,
+    tags_col: Option<usize>,
+) -> String {
+    let state = task.state.to_uppercase();
+    let keyword = match task.done {
+        true if keywords.done.contains(&state) => &state,
+        true => &keywords.done[0],
+        false if keywords.todo.contains(&state) => &state,
+        false => &keywords.todo[0],
+    };
+    let mut out = format!("{} {}", "*".repeat(level), keyword);
+    let cookie = match task.priority {
+        Priority::High => " [#A]",
+        Priority::Medium => " [#B]",
+        Priority::Low => " [#C]",
+        Priority::None => "",
+    };
+    out.push_str(cookie);
+    let mut text: Vec<&str> = task.title.split_whitespace().collect();
+    let mut tags = vec![];
+    while let Some(tag) = text.last().and_then(|x| x.strip_prefix('#')) {
+        if !is_tag(tag) {
+            break;
+        }
+        tags.insert(0, tag);
+        text.pop();
+    }
+    if !text.is_empty() {
+        out.push(' ');
+        out.push_str(&text.join(" "));
+    }
+    if !tags.is_empty() {
+        let gap = tags_col.map_or(1, |col| col.saturating_sub(width(&out)).max(1));
+        out.push_str(&" ".repeat(gap));
+        out.push_str(&format!(":{}:", tags.join(":")));
+    }
+    out
+}
+
+// the planning line of the task, timestamps which did not change since old
+// are kept as they are written in the planning
+fn render_planning(planning: Option<&Planning>, old: &Task, task: &Task) -> Option<String> {
+    let default = Planning::default();
+    let planning = planning.unwrap_or(&default);
+    let closed = match task.done_at == old.done_at {
+        true => planning.closed.clone(),
+        false => task.done_at.map(|x| format_timestamp(x, false, None)),
+    };
+    let due = match (task.due, &task.recurrence) == (old.due, &old.recurrence) {
+        true => None,
+        false => Some(
+            task.due
+                .map(|x| format_timestamp(x, true, task.recurrence.as_deref())),
+        ),
+    };
+    // the due date is the deadline unless the task only has a scheduled date
+    let (deadline, scheduled) = match due {
+        None => (planning.deadline.clone(), planning.scheduled.clone()),
+        Some(due) if planning.deadline.is_none() && planning.scheduled.is_some() => (None, due),
+        Some(due) => (due, planning.scheduled.clone()),
+    };
+    let parts: Vec<String> = [
+        ("CLOSED", closed),
+        ("DEADLINE", deadline),
+        ("SCHEDULED", scheduled),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| value.map(|x| format!("{}: {}", key, x)))
+    .collect();
+    match parts.is_empty() {
+        true => None,
+        false => Some(format!("{}{}", planning.indent, parts.join(" "))),
+    }
+}
+
+fn split_eol(line: &str) -> (&str, &str) {
+    let content = line.trim_end_matches(['\n', '\r']);
+    (content, &line[content.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "#+TITLE: Project
+#+TODO: TODO DOING | DONE
+
+Some notes.
+
+* Release
+** TODO [#A] write docs                                          :docs:
+   DEADLINE: <2024-03-05 Tue> SCHEDULED: <2024-03-01 Fri>
+   The usage section is missing.
+** DOING fix login :work:
+   SCHEDULED: <2024-03-08 Fri 10:00 +1w>
+** DONE plan release
+   CLOSED: [2024-02-28 Wed 17:30]
+*** notes
+* Ideas
+";
+
+    fn date(s: &str) -> DateTime<Local> {
+        let date = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&date).unwrap()
+    }
+
+    #[test]
+    fn load_headings() {
+        let list = load_org(CONTENT);
+        let tasks: Vec<_> = list
+            .items
+            .iter()
+            .map(|x| (x.title.as_str(), x.state.as_str(), x.done))
+            .collect();
+        assert_eq!(
+            tasks,
+            vec![
+                ("write docs #docs", "todo", false),
+                ("fix login #work", "doing", false),
+                ("plan release", "done", true),
+            ]
+        );
+        assert_eq!(list.items[0].priority, Priority::High);
+        assert_eq!(list.items[0].due, Some(date("2024-03-05 00:00")));
+        assert_eq!(list.items[1].due, Some(date("2024-03-08 10:00")));
+        assert_eq!(list.items[1].recurrence.as_deref(), Some("FREQ=WEEKLY"));
+        assert_eq!(list.items[2].done_at, Some(date("2024-02-28 17:30")));
+    }
+
+    #[test]
+    fn save_minimal_diff() {
+        let list = load_org(CONTENT);
+        assert_eq!(save_org(CONTENT, &list), CONTENT);
+
+        let mut list = load_org(CONTENT);
+        list.items[0].state = String::from("done");
+        list.items[0].done = true;
+        list.items[0].done_at = Some(date("2024-03-04 09:00"));
+        list.items[1].due = Some(date("2024-03-15 10:00"));
+        list.del(Some(Position::AtIndex(2))).unwrap();
+        let mut task = Task::new("celebrate #team");
+        task.priority = Priority::Low;
+        list.add(None, task).unwrap();
+        let expected = "#+TITLE: Project
+#+TODO: TODO DOING | DONE
+
+Some notes.
+
+* Release
+** DONE [#A] write docs                                          :docs:
+   CLOSED: [2024-03-04 Mon 09:00] DEADLINE: <2024-03-05 Tue> SCHEDULED: <2024-03-01 Fri>
+   The usage section is missing.
+** DOING fix login :work:
+   SCHEDULED: <2024-03-15 Fri 10:00 +1w>
+** TODO [#C] celebrate :team:
+* Ideas
+";
+        assert_eq!(save_org(CONTENT, &list), expected);
+    }
+
+    #[test]
+    fn keep_words_which_are_not_tags() {
+        let mut list = load_org("");
+        for title in [
+            "see #42 first",
+            "fix bug #follow-up",
+            "fix #follow-up #work #ops",
+        ] {
+            list.add(None, Task::new(title)).unwrap();
+        }
+        let content = save_org("", &list);
+        assert_eq!(
+            content,
+            "* TODO see #42 first\n* TODO fix bug #follow-up\n* TODO fix #follow-up :work:ops:\n"
+        );
+        let titles: Vec<_> = load_org(&content)
+            .items
+            .into_iter()
+            .map(|x| x.title)
+            .collect();
+        assert_eq!(
+            titles,
+            vec![
+                "see #42 first",
+                "fix bug #follow-up",
+                "fix #follow-up #work #ops"
+            ]
+        );
+    }
+
+    #[test]
+    fn delete_keeps_kept_children() {
+        let content = "* TODO parent\n** notes\n** TODO child\n*** more\n* TODO next\n";
+        let mut list = load_org(content);
+        list.del(Some(Position::AtIndex(0))).unwrap();
+        assert_eq!(
+            save_org(content, &list),
+            "** TODO child\n*** more\n* TODO next\n"
+        );
+    }
+
+    #[test]
+    fn export_and_import() {
+        let workflow = Workflow::default();
+        let mut list = load_org(CONTENT);
+        list.items[1].state = String::from("review");
+        let content = export_org(&list, &workflow);
+        assert!(content.starts_with("#+TODO: TODO DOING REVIEW | DONE\n* TODO [#A] write docs :docs:\nDEADLINE: <2024-03-05 Tue>\n:PROPERTIES:\n"));
+        let tasks = import_org(&content, &workflow);
+        assert_eq!(tasks, list.items);
+    }
+}
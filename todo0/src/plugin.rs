@@ -0,0 +1,248 @@
+// the protocol between todo0 and its wasm plugins, see wasm.rs for the host.
+// everything is exchanged as json strings:
+//   describe()          -> PluginInfo
+//   command(CommandInput) -> the text to print
+//   render(RenderInput)   -> the rendered list
+// while a plugin runs, it can call these host functions:
+//   todo_items("")              -> [Task, ...]
+//   todo_add(AddRequest)        -> { "id": <task id> }
+//   todo_edit(EditRequest)      -> { "id": <task id> }
+//   todo_delete(DeleteRequest)  -> { "deleted": <count> }
+// positions are 1-based like on the command line.
+#![cfg_attr(not(feature = "plugins"), allow(dead_code))]
+
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::todo::*;
+use crate::workflow::Workflow;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct PluginInfo {
+    pub name: String,
+    #[serde(default)]
+    pub commands: Vec<PluginCommand>,
+    #[serde(default)]
+    pub renderers: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct PluginCommand {
+    pub name: String,
+    #[serde(default)]
+    pub help: String,
+}
+
+#[derive(Serialize)]
+pub struct CommandInput<'a> {
+    pub command: &'a str,
+    pub args: &'a [String],
+}
+
+#[derive(Serialize)]
+pub struct RenderInput<'a> {
+    pub renderer: &'a str,
+    pub items: &'a [Task],
+}
+
+#[derive(Deserialize)]
+struct AddRequest {
+    title: String,
+    position: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct EditRequest {
+    position: usize,
+    title: Option<String>,
+    done: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct DeleteRequest {
+    positions: Vec<usize>,
+}
+
+pub fn api_items(list: &TodoList) -> Result<String, String> {
+    serde_json::to_string(&list.items).map_err(|err| tr!("encode_json_failed", error = err))
+}
+
+pub fn api_add(list: &mut TodoList, input: &str) -> Result<String, String> {
+    let req: AddRequest = decode(input)?;
+    let pos = match req.position {
+        None => None,
+        Some(position) => Some(Position::AtIndex(check_position(list, position, 1)?)),
+    };
+    match list.add(pos, Task::new(&req.title)) {
+        Ok(res) => Ok(json!({ "id": result_id(res) }).to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+pub fn api_edit(list: &mut TodoList, workflow: &Workflow, input: &str) -> Result<String, String> {
+    let req: EditRequest = decode(input)?;
+    let index = check_position(list, req.position, 0)?;
+    let mut task = list.items[index].clone();
+    if let Some(title) = req.title {
+        task.title = title;
+    }
+    if let Some(done) = req.done.filter(|x| *x != task.done) {
+        let state = match done {
+            true => workflow.final_state(),
+            false => workflow.initial(),
+        };
+        task = workflow.set_state(&task, state);
+    }
+    match list.edit(Some(Position::AtIndex(index)), task) {
+        Ok(res) => Ok(json!({ "id": result_id(res) }).to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+pub fn api_delete(list: &mut TodoList, input: &str) -> Result<String, String> {
+    let req: DeleteRequest = decode(input)?;
+    let mut positions = vec![];
+    for position in req.positions {
+        positions.push(Some(Position::AtIndex(check_position(list, position, 0)?)));
+    }
+    let deleted = list.delete(&positions).iter().filter(|x| x.is_ok()).count();
+    Ok(json!({ "deleted": deleted }).to_string())
+}
+
+fn decode<'a, T: Deserialize<'a>>(input: &'a str) -> Result<T, String> {
+    serde_json::from_str(input).map_err(|err| {
+        tr!(
+            "invalid_request",
+            request = format!("{:?}", input),
+            error = err
+        )
+    })
+}
+
+// the 0-based index of the 1-based position, extra allows the position after the last task
+fn check_position(list: &TodoList, position: usize, extra: usize) -> Result<usize, String> {
+    let max = list.items.len() + extra;
+    if position == 0 || position > max {
+        return Err(tr!("position_out_of_range", position = position, max = max));
+    }
+    Ok(position - 1)
+}
+
+fn result_id(res: ActionResult) -> u64 {
+    match res {
+        ActionResult::Inserted(id) | ActionResult::Updated(id) | ActionResult::Deleted(id) => id.0,
+    }
+}
+
+// the .wasm files in the plugin dir, sorted by name. a missing dir has none
+pub fn plugin_paths(config: &Config) -> Result<Vec<PathBuf>, String> {
+    let dir = config.plugin_dir();
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => {
+            return Err(tr!(
+                "read_dir_failed",
+                dir = format!("{:?}", dir),
+                error = err
+            ))
+        }
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|x| x.path()))
+        .filter(|path| path.extension().is_some_and(|x| x == "wasm"))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+// run the command of a plugin, returns false if no plugin has the command
+#[cfg(feature = "plugins")]
+pub fn run_plugin_command(
+    todo_list: &mut TodoList,
+    config: &Config,
+    args: &[String],
+) -> Result<bool, String> {
+    crate::wasm::run_command(todo_list, config, args)
+}
+
+#[cfg(not(feature = "plugins"))]
+pub fn run_plugin_command(_: &mut TodoList, _: &Config, _: &[String]) -> Result<bool, String> {
+    Ok(false)
+}
+
+// render the list with the renderer of a plugin, e.g. "list --format html"
+#[cfg(feature = "plugins")]
+pub fn render_with_plugin(
+    todo_list: &TodoList,
+    config: &Config,
+    renderer: &str,
+) -> Result<String, String> {
+    crate::wasm::render(todo_list, config, renderer)
+}
+
+#[cfg(not(feature = "plugins"))]
+pub fn render_with_plugin(_: &TodoList, _: &Config, renderer: &str) -> Result<String, String> {
+    Err(tr!(
+        "unknown_format_no_plugins",
+        format = format!("{:?}", renderer)
+    ))
+}
+
+#[cfg(feature = "plugins")]
+pub fn describe_plugins(paths: &[PathBuf]) -> Result<Vec<PluginInfo>, String> {
+    crate::wasm::describe(paths)
+}
+
+#[cfg(not(feature = "plugins"))]
+pub fn describe_plugins(_: &[PathBuf]) -> Result<Vec<PluginInfo>, String> {
+    Err(tr!("plugins_disabled"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(list: &TodoList) -> Vec<&str> {
+        list.items.iter().map(|x| x.title.as_str()).collect()
+    }
+
+    #[test]
+    fn host_api() {
+        let workflow = Workflow::default();
+        let mut list = TodoList::new();
+        api_add(&mut list, r#"{"title": "A"}"#).unwrap();
+        api_add(&mut list, r#"{"title": "B", "position": 1}"#).unwrap();
+        api_add(&mut list, r#"{"title": "C", "position": 3}"#).unwrap();
+        assert_eq!(titles(&list), vec!["B", "A", "C"]);
+        assert!(api_add(&mut list, r#"{"title": "D", "position": 9}"#).is_err());
+        assert!(api_add(&mut list, r#"{"title": ""}"#).is_err());
+
+        api_edit(&mut list, &workflow, r#"{"position": 2, "done": true}"#).unwrap();
+        assert!(list.items[1].done);
+        assert_eq!(list.items[1].state, "done");
+        api_edit(&mut list, &workflow, r#"{"position": 1, "title": "B0"}"#).unwrap();
+        assert_eq!(titles(&list), vec!["B0", "A", "C"]);
+
+        let res = api_delete(&mut list, r#"{"positions": [1, 3]}"#).unwrap();
+        assert_eq!(res, r#"{"deleted":2}"#);
+        assert_eq!(titles(&list), vec!["A"]);
+        assert!(api_delete(&mut list, r#"{"positions": [2]}"#).is_err());
+
+        let items: Vec<Task> = serde_json::from_str(&api_items(&list).unwrap()).unwrap();
+        assert_eq!(items, list.items);
+    }
+
+    #[test]
+    fn decode_info() {
+        let info: PluginInfo =
+            serde_json::from_str(r#"{"name": "html", "renderers": ["html"]}"#).unwrap();
+        assert!(info.commands.is_empty());
+        assert_eq!(info.renderers, vec!["html"]);
+    }
+}
@@ -0,0 +1,267 @@
+use crate::i18n::tr;
+use crate::todo::*;
+use crate::workflow::Workflow;
+
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path;
+
+const MARKERS: [&str; 3] = ["TODO", "FIXME", "XXX"];
+const COMMENT_STARTS: [&str; 7] = ["//", "#", "/*", "*", "--", ";", "<!--"];
+// directories which are not walked, besides hidden ones
+const SKIP_DIRS: [&str; 2] = ["target", "node_modules"];
+
+// a TODO/FIXME/XXX comment found in a source file
+#[derive(PartialEq, Debug)]
+pub struct Comment {
+    pub id: TaskId,
+    pub source: Source,
+    pub marker: String,
+    pub text: String,
+}
+
+impl Comment {
+    pub fn title(&self) -> String {
+        match self.text.is_empty() {
+            true => self.marker.clone(),
+            false => format!("{}: {}", self.marker, self.text),
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Debug)]
+pub struct Summary {
+    pub added: usize,
+    pub updated: usize,
+    pub reopened: usize,
+    pub done: usize,
+}
+
+// the list synced by "scan <dir>", kept in the scanned dir
+pub fn get_scan_file_path(dir: &path::Path) -> path::PathBuf {
+    dir.join(".todo0-scan.json")
+}
+
+// walk the dir and collect the comments of all text files, paths are relative to dir
+pub fn scan_dir(dir: &path::Path) -> Result<Vec<Comment>, String> {
+    let mut files = vec![];
+    collect_files(dir, &mut files)?;
+    files.sort();
+    let mut comments = vec![];
+    for file in files {
+        // skip binary files
+        let content = match fs::read(&file).map(String::from_utf8) {
+            Ok(Ok(content)) => content,
+            Ok(Err(_)) => continue,
+            Err(err) => {
+                return Err(tr!(
+                    "read_file_failed",
+                    file = format!("{:?}", file),
+                    error = err
+                ))
+            }
+        };
+        let rel = file.strip_prefix(dir).unwrap_or(&file);
+        let rel: Vec<_> = rel.iter().map(|x| x.to_string_lossy()).collect();
+        comments.extend(scan_text(&rel.join("/"), &content));
+    }
+    Ok(comments)
+}
+
+fn collect_files(dir: &path::Path, files: &mut Vec<path::PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|err| tr!("read_dir_failed", dir = format!("{:?}", dir), error = err))?;
+    for entry in entries {
+        let entry =
+            entry.map_err(|err| tr!("read_dir_failed", dir = format!("{:?}", dir), error = err))?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let file_type = entry.file_type().map_err(|err| {
+            tr!(
+                "read_failed",
+                path = format!("{:?}", entry.path()),
+                error = err
+            )
+        })?;
+        if file_type.is_dir() && !SKIP_DIRS.contains(&name.as_str()) {
+            collect_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
+// find the comments in the content of a file. the id is a hash of the file,
+// the marker, the text and the number of the same comments before it in the
+// file, so it stays the same when the comment moves to another line.
+pub fn scan_text(file: &str, content: &str) -> Vec<Comment> {
+    let mut comments: Vec<Comment> = vec![];
+    for (idx, line) in content.lines().enumerate() {
+        let (marker, text) = match find_marker(line) {
+            Some(found) => found,
+            None => continue,
+        };
+        let seen = comments
+            .iter()
+            .filter(|x| x.marker == marker && x.text == text)
+            .count();
+        let mut hasher = Sha256::new();
+        for part in [file, marker, &text, &seen.to_string()] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        let hash = hasher.finalize();
+        comments.push(Comment {
+            id: TaskId(u64::from_be_bytes(hash[..8].try_into().unwrap())),
+            source: Source {
+                file: file.to_string(),
+                line: idx + 1,
+            },
+            marker: marker.to_string(),
+            text,
+        });
+    }
+    comments
+}
+
+// "// TODO: text", "# FIXME(bob) text", "/* XXX text */", the marker must be in a comment
+fn find_marker(line: &str) -> Option<(&'static str, String)> {
+    let comment_at = COMMENT_STARTS
+        .iter()
+        .filter_map(|start| line.find(start))
+        .min()?;
+    let comment = &line[comment_at..];
+    for (at, _) in comment.match_indices(|c: char| c.is_ascii_uppercase()) {
+        let rest = &comment[at..];
+        let marker = match MARKERS.iter().find(|x| rest.starts_with(*x)) {
+            Some(marker) => *marker,
+            None => continue,
+        };
+        let before = comment[..at].chars().last();
+        let after = rest[marker.len()..].chars().next();
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        if is_word(before) || is_word(after) {
+            continue;
+        }
+        let mut text = rest[marker.len()..].trim_start();
+        // the author, e.g. TODO(bob)
+        if text.starts_with('(') {
+            if let Some(end) = text.find(')') {
+                text = &text[end + 1..];
+            }
+        }
+        let text = text
+            .trim_start_matches([':', ' ', '-'])
+            .trim_end_matches("-->")
+            .trim_end_matches("*/")
+            .trim();
+        return Some((marker, text.to_string()));
+    }
+    None
+}
+
+// add the new comments, update the moved or changed ones, reopen the comments
+// which came back and mark the tasks of vanished comments as done. tasks
+// without a source are left as they are.
+pub fn sync(list: &mut TodoList, workflow: &Workflow, comments: &[Comment]) -> Summary {
+    let mut summary = Summary::default();
+    for comment in comments {
+        let title = comment.title();
+        match list.items.iter_mut().find(|x| x.id == comment.id) {
+            Some(task) => {
+                if task.done {
+                    *task = workflow.set_state(task, workflow.initial());
+                    summary.reopened += 1;
+                }
+                if task.title != title || task.source.as_ref() != Some(&comment.source) {
+                    task.title = title;
+                    task.source = Some(comment.source.clone());
+                    summary.updated += 1;
+                }
+            }
+            None => {
+                let task = Task {
+                    id: comment.id,
+                    title,
+                    source: Some(comment.source.clone()),
+                    ..Default::default()
+                };
+                list.add(None, task).unwrap();
+                summary.added += 1;
+            }
+        }
+    }
+    for task in list.items.iter_mut() {
+        let vanished = task.source.is_some() && !comments.iter().any(|x| x.id == task.id);
+        if vanished && !task.done {
+            *task = workflow.set_state(task, workflow.final_state());
+            summary.done += 1;
+        }
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_comments() {
+        let content = [
+            "fn main() {",
+            "    // TODO: handle errors",
+            "    let todo = 1; // FIXME(bob) overflow",
+            "    let s = \"TODO not a comment\";",
+            "    /* XXX */",
+            "    // TODOS are not markers",
+            "}",
+        ]
+        .join("\n");
+        let comments: Vec<_> = scan_text("src/main.rs", &content)
+            .into_iter()
+            .map(|x| (x.source.line, x.title()))
+            .collect();
+        assert_eq!(
+            comments,
+            vec![
+                (2, String::from("TODO: handle errors")),
+                (3, String::from("FIXME: overflow")),
+                (5, String::from("XXX")),
+            ]
+        );
+    }
+
+    #[test]
+    fn sync_keeps_ids() {
+        let workflow = Workflow::default();
+        let mut list = TodoList::new();
+        let first = scan_text("a.rs", "// TODO one\n// TODO two\n// TODO two\n");
+        let summary = sync(&mut list, &workflow, &first);
+        assert_eq!(summary.added, 3);
+        assert_ne!(list.items[1].id, list.items[2].id);
+
+        // both moved down a line, the second "two" vanished
+        let second = scan_text("a.rs", "\n// TODO one\n// TODO two\n");
+        assert_eq!(second[0].id, first[0].id);
+        let summary = sync(&mut list, &workflow, &second);
+        assert_eq!(
+            summary,
+            Summary {
+                added: 0,
+                updated: 2,
+                reopened: 0,
+                done: 1
+            }
+        );
+        assert_eq!(list.items[0].source.as_ref().unwrap().line, 2);
+        assert!(!list.items[1].done);
+        assert!(list.items[2].done);
+
+        // rescanning the same content changes nothing
+        let summary = sync(&mut list, &workflow, &second);
+        assert_eq!(summary, Summary::default());
+    }
+}
@@ -0,0 +1,300 @@
+use crate::batch::parse_script;
+use crate::cmd::*;
+use crate::config::*;
+use crate::crypt::Secret;
+use crate::file::*;
+use crate::help::*;
+use crate::hook;
+use crate::i18n::tr;
+use crate::todo::*;
+use crate::watch;
+
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{CompletionType, Context, Editor, Helper};
+use std::fs;
+use std::path::Path;
+
+const PROMPT: &str = "todo0> ";
+
+// the commands completed as the first word
+const COMMANDS: [&str; 32] = [
+    "add",
+    "assign",
+    "block",
+    "board",
+    "check",
+    "del",
+    "delete",
+    "diff",
+    "edit",
+    "exit",
+    "export",
+    "graph",
+    "help",
+    "import",
+    "list",
+    "mv-state",
+    "next",
+    "plugins",
+    "prio",
+    "quit",
+    "ready",
+    "report",
+    "restore",
+    "scan",
+    "snapshots",
+    "start",
+    "stats",
+    "stop",
+    "template",
+    "unassign",
+    "unblock",
+    "uncheck",
+];
+
+// completes commands and task titles, the titles are updated before each line
+struct ShellHelper {
+    titles: Vec<String>,
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, words) = complete(&line[..pos], &self.titles);
+        let pairs = words
+            .into_iter()
+            .map(|x| Pair {
+                display: x.clone(),
+                replacement: x,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+// the start of the word before the cursor and what it can be completed to: a
+// command for the first word, the current title after "edit N" and otherwise
+// the titles which start with the word
+fn complete(line: &str, titles: &[String]) -> (usize, Vec<String>) {
+    // only the last command of "add x; check 2" is completed
+    let cmd_start = line.rfind(';').map_or(0, |x| x + 1);
+    // whitespace like U+3000 is more than one byte
+    let start = line
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map_or(0, |(i, c)| i + c.len_utf8())
+        .max(cmd_start);
+    let word = &line[start..];
+    let before: Vec<&str> = line[cmd_start..start].split_whitespace().collect();
+    let mut words: Vec<String> = match before.as_slice() {
+        [] => COMMANDS
+            .iter()
+            .filter(|x| x.starts_with(word))
+            .map(|x| x.to_string())
+            .collect(),
+        ["edit", pos] if word.is_empty() => pos
+            .parse::<usize>()
+            .ok()
+            .and_then(|pos| titles.get(pos.wrapping_sub(1)))
+            .cloned()
+            .into_iter()
+            .collect(),
+        _ if word.is_empty() => vec![],
+        _ => {
+            let word = word.to_lowercase();
+            titles
+                .iter()
+                .filter(|x| x.to_lowercase().starts_with(&word))
+                .cloned()
+                .collect()
+        }
+    };
+    words.dedup();
+    (start, words)
+}
+
+fn titles(todo_list: &TodoList) -> Vec<String> {
+    todo_list.items.iter().map(|x| x.title.clone()).collect()
+}
+
+// read commands until "exit" or ctrl-d and run them on the list in memory. the
+// list is saved after each command which changes it, and loaded again when
+// another process saved the file in between.
+pub fn run_shell(
+    todo_list: &mut TodoList,
+    config: &Config,
+    file_path: &Path,
+    secret: &Option<Secret>,
+) -> Result<(), String> {
+    let editor_config = rustyline::Config::builder()
+        .completion_type(CompletionType::List)
+        .auto_add_history(true)
+        .build();
+    let mut editor: Editor<ShellHelper, DefaultHistory> =
+        Editor::with_config(editor_config).map_err(|err| tr!("start_shell_failed", error = err))?;
+    editor.set_helper(Some(ShellHelper { titles: vec![] }));
+    let history_path = get_config_path().with_file_name("history");
+    // the lines contain task titles, which are not written in plaintext next
+    // to an encrypted list
+    let keep_history = secret.is_none();
+    if keep_history {
+        // there is no history before the first session
+        let _ = editor.load_history(&history_path);
+    }
+
+    let mut base = todo_list.clone();
+    let mut loaded_at = watch::modified_time(file_path);
+    loop {
+        if watch::modified_time(file_path) != loaded_at {
+            match load_from_file(file_path, secret.as_ref()) {
+                Ok(list) => {
+                    println!("{}", tr!("reloaded"));
+                    *todo_list = list;
+                    base = todo_list.clone();
+                    loaded_at = watch::modified_time(file_path);
+                }
+                Err(err) => eprintln!("{}", err),
+            }
+        }
+        if let Some(helper) = editor.helper_mut() {
+            helper.titles = titles(todo_list);
+        }
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(tr!("read_line_failed", error = err)),
+        };
+        let commands = match parse_script(&line) {
+            Ok(commands) => commands,
+            Err(err) => {
+                eprintln!("{}", err);
+                continue;
+            }
+        };
+        let mut exit = false;
+        for (_, args) in commands {
+            // like in main, the post hooks wait until the change is saved
+            hook::defer_post_hooks();
+            let res = match args[0].as_str() {
+                "exit" | "quit" => {
+                    exit = true;
+                    break;
+                }
+                "help" => {
+                    print_help();
+                    Ok(false)
+                }
+                "snapshots" => cmd_snapshots(file_path, secret.as_ref(), &args[1..]).map(|_| false),
+                "diff" => {
+                    cmd_diff(todo_list, file_path, secret.as_ref(), &args[1..]).map(|_| false)
+                }
+                "restore" => {
+                    cmd_restore(todo_list, file_path, secret.as_ref(), &args[1..]).map(|_| true)
+                }
+                "shell" | "batch" | "-c" | "reset" | "encrypt" | "decrypt" => {
+                    Err(tr!("not_in_shell"))
+                }
+                "list" if args.iter().any(|x| x == "--follow") => Err(tr!("not_in_shell")),
+                _ => crate::run_command(todo_list, config, file_path, secret.as_ref(), &args),
+            };
+            match res {
+                Ok(true) => {
+                    let list = todo_list.clone();
+                    // the change stays in memory until the file can be read again
+                    match crate::merge_external(file_path, secret, loaded_at, &base, list) {
+                        Ok(list) => *todo_list = list,
+                        Err(err) => {
+                            hook::discard_deferred_hooks();
+                            eprintln!("{}", err);
+                            break;
+                        }
+                    }
+                    if let Err(err) =
+                        crate::save_list(file_path, todo_list, secret.as_ref(), config)
+                    {
+                        hook::discard_deferred_hooks();
+                        return Err(err);
+                    }
+                    hook::run_deferred_hooks();
+                    base = todo_list.clone();
+                    loaded_at = watch::modified_time(file_path);
+                }
+                Ok(false) => hook::discard_deferred_hooks(),
+                // the commands after a failed one are not run, like in a batch
+                Err(err) => {
+                    hook::discard_deferred_hooks();
+                    eprintln!("{}", err);
+                    break;
+                }
+            }
+        }
+        if exit {
+            break;
+        }
+    }
+    if !keep_history {
+        return Ok(());
+    }
+    if let Some(dir) = history_path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    editor.save_history(&history_path).map_err(|err| {
+        tr!(
+            "save_history_failed",
+            file = format!("{:?}", history_path),
+            error = err
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_words() {
+        let titles = vec![
+            String::from("write docs"),
+            String::from("fix login"),
+            String::from("Write tests"),
+        ];
+        let words = |x: Vec<&str>| x.into_iter().map(String::from).collect::<Vec<_>>();
+        assert_eq!(
+            complete("un", &titles),
+            (0, words(vec!["unassign", "unblock", "uncheck"]))
+        );
+        assert_eq!(complete("add x; ch", &titles), (7, words(vec!["check"])));
+        assert_eq!(complete("edit 2 ", &titles), (7, words(vec!["fix login"])));
+        assert_eq!(complete("edit 9 ", &titles), (7, vec![]));
+        assert_eq!(complete("edit\u{3000}", &titles), (7, vec![]));
+        assert_eq!(complete("add\u{a0}wr", &titles).0, 5);
+        assert_eq!(
+            complete("add before wri", &titles),
+            (11, words(vec!["write docs", "Write tests"]))
+        );
+        assert_eq!(complete("check ", &titles), (6, vec![]));
+    }
+}
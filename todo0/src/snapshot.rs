@@ -0,0 +1,311 @@
+use crate::crypt::Secret;
+use crate::file::*;
+use crate::i18n::tr;
+use crate::todo::*;
+
+use chrono::{DateTime, Days, Local, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path;
+
+// snapshots are named by the time they were taken, so that later ones sort later
+const NAME_FORMAT: &str = "%Y%m%d-%H%M%S%.3f";
+
+// how many snapshots are kept, set as "snapshots" in the config. the last
+// ones and the last one of each day of the last days are kept, 0 and 0
+// turns snapshots off.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct Retention {
+    pub keep_last: usize,
+    pub keep_daily: usize,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            keep_last: 20,
+            keep_daily: 7,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Added(usize, String),
+    Removed(String),
+    Renamed(usize, String, String),
+    Checked(usize, String),
+    Unchecked(usize, String),
+}
+
+// e.g. todo.json -> todo.snapshots/
+pub fn get_snapshot_dir(file_path: &path::Path) -> path::PathBuf {
+    let stem = file_path
+        .file_stem()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default();
+    file_path.with_file_name(format!("{}.snapshots", stem))
+}
+
+// the snapshot names, newest first. other files in the dir are ignored
+pub fn list_snapshots(file_path: &path::Path) -> Result<Vec<String>, String> {
+    let dir = get_snapshot_dir(file_path);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(err) => {
+            return Err(tr!(
+                "read_dir_failed",
+                dir = format!("{:?}", dir),
+                error = err
+            ))
+        }
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            name.strip_suffix(".json").map(String::from)
+        })
+        .filter(|name| NaiveDateTime::parse_from_str(name, NAME_FORMAT).is_ok())
+        .collect();
+    names.sort_by(|a, b| b.cmp(a));
+    Ok(names)
+}
+
+// save the list as the snapshot of now, encrypted like the file, and delete
+// the snapshots which are not kept anymore
+pub fn take_snapshot(
+    file_path: &path::Path,
+    list: &TodoList,
+    secret: Option<&Secret>,
+    retention: &Retention,
+    now: DateTime<Local>,
+) -> Result<(), String> {
+    if retention.keep_last == 0 && retention.keep_daily == 0 {
+        return Ok(());
+    }
+    let dir = get_snapshot_dir(file_path);
+    fs::create_dir_all(&dir)
+        .map_err(|err| tr!("create_dir_failed", dir = format!("{:?}", dir), error = err))?;
+    let name = now.format(NAME_FORMAT).to_string();
+    save_to_file(&snapshot_path(file_path, &name), list.clone(), secret)?;
+    let names = list_snapshots(file_path)?;
+    for name in expired(&names, retention, now.date_naive()) {
+        let path = snapshot_path(file_path, &name);
+        fs::remove_file(&path)
+            .map_err(|err| tr!("delete_failed", path = format!("{:?}", path), error = err))?;
+    }
+    Ok(())
+}
+
+// the snapshot by name, or by its position in "snapshots" where 1 is the newest
+pub fn load_snapshot(
+    file_path: &path::Path,
+    snapshot: &str,
+    secret: Option<&Secret>,
+) -> Result<(String, TodoList), String> {
+    let names = list_snapshots(file_path)?;
+    let name = match snapshot.parse::<usize>() {
+        Ok(idx) if idx >= 1 && idx <= names.len() => names[idx - 1].clone(),
+        _ if names.iter().any(|x| x == snapshot) => snapshot.to_string(),
+        _ => return Err(tr!("unknown_snapshot", name = format!("{:?}", snapshot))),
+    };
+    let list = load_from_file(&snapshot_path(file_path, &name), secret)?;
+    Ok((name, list))
+}
+
+// write all snapshots again with the new secret, when the file is encrypted
+// or decrypted
+pub fn reencrypt_snapshots(
+    file_path: &path::Path,
+    from: Option<&Secret>,
+    to: Option<&Secret>,
+) -> Result<(), String> {
+    for name in list_snapshots(file_path)? {
+        let path = snapshot_path(file_path, &name);
+        // a snapshot may already be written with the new secret
+        let list = load_from_file(&path, from.or(to))?;
+        save_to_file(&path, list, to)?;
+    }
+    Ok(())
+}
+
+fn snapshot_path(file_path: &path::Path, name: &str) -> path::PathBuf {
+    get_snapshot_dir(file_path).join(format!("{}.json", name))
+}
+
+// the snapshots which are neither one of the last ones nor the last one of a
+// recent day. names are newest first.
+fn expired(names: &[String], retention: &Retention, today: NaiveDate) -> Vec<String> {
+    let mut kept: HashSet<&str> = names
+        .iter()
+        .take(retention.keep_last)
+        .map(|x| x.as_str())
+        .collect();
+    let first_day = today
+        .checked_sub_days(Days::new(retention.keep_daily as u64))
+        .unwrap_or(NaiveDate::MIN);
+    let mut days = HashSet::new();
+    for name in names {
+        let day = name
+            .get(..8)
+            .and_then(|x| NaiveDate::parse_from_str(x, "%Y%m%d").ok());
+        if let Some(day) = day {
+            if day > first_day && days.insert(day) {
+                kept.insert(name);
+            }
+        }
+    }
+    names
+        .iter()
+        .filter(|x| !kept.contains(x.as_str()))
+        .cloned()
+        .collect()
+}
+
+// the changes from the old list to the new one, by task id. positions are
+// the ones in the new list.
+pub fn diff(old: &TodoList, new: &TodoList) -> Vec<Change> {
+    let mut changes = vec![];
+    for (idx, task) in new.items.iter().enumerate() {
+        let pos = idx + 1;
+        let before = match old.items.iter().find(|x| x.id == task.id) {
+            Some(before) => before,
+            None => {
+                changes.push(Change::Added(pos, task.title.clone()));
+                continue;
+            }
+        };
+        if before.title != task.title {
+            changes.push(Change::Renamed(
+                pos,
+                before.title.clone(),
+                task.title.clone(),
+            ));
+        }
+        match (before.done, task.done) {
+            (false, true) => changes.push(Change::Checked(pos, task.title.clone())),
+            (true, false) => changes.push(Change::Unchecked(pos, task.title.clone())),
+            _ => {}
+        }
+    }
+    for task in old.items.iter() {
+        if !new.items.iter().any(|x| x.id == task.id) {
+            changes.push(Change::Removed(task.title.clone()));
+        }
+    }
+    changes
+}
+
+pub fn format_change(change: &Change) -> String {
+    match change {
+        Change::Added(pos, title) => tr!("change_added", pos = pos, title = title),
+        Change::Removed(title) => tr!("change_removed", title = title),
+        Change::Renamed(pos, from, to) => tr!("change_renamed", pos = pos, from = from, to = to),
+        Change::Checked(pos, title) => tr!("change_checked", pos = pos, title = title),
+        Change::Unchecked(pos, title) => tr!("change_unchecked", pos = pos, title = title),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn rotate() {
+        let all = names(&[
+            "20240310-120000",
+            "20240310-110000",
+            "20240310-100000",
+            "20240309-180000",
+            "20240309-090000",
+            "20240301-120000",
+        ]);
+        let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let retention = Retention {
+            keep_last: 2,
+            keep_daily: 7,
+        };
+        // the last two, and the last ones of the 10th and 9th. the 1st is too old
+        assert_eq!(
+            expired(&all, &retention, today),
+            names(&["20240310-100000", "20240309-090000", "20240301-120000"])
+        );
+        let retention = Retention {
+            keep_last: 0,
+            keep_daily: 1,
+        };
+        assert_eq!(expired(&all, &retention, today), all[1..].to_vec());
+    }
+
+    #[test]
+    fn take_and_load() {
+        let dir = std::env::temp_dir().join(format!("todo0-test-{}", rand::random::<u64>()));
+        let file_path = dir.join("todo.json");
+        let snapshot_dir = get_snapshot_dir(&file_path);
+        fs::create_dir_all(&snapshot_dir).unwrap();
+        // files which are not snapshots are neither listed nor deleted
+        fs::write(snapshot_dir.join("a日本語x.json"), "{}").unwrap();
+        fs::write(snapshot_dir.join("notes.txt"), "").unwrap();
+
+        let retention = Retention {
+            keep_last: 2,
+            keep_daily: 0,
+        };
+        let mut list = TodoList::new();
+        let now = Local::now();
+        for (secs, title) in [(0, "a"), (1, "b"), (2, "c")] {
+            list.add(None, Task::new(title)).unwrap();
+            let at = now + chrono::Duration::seconds(secs);
+            take_snapshot(&file_path, &list, None, &retention, at).unwrap();
+        }
+        let names = list_snapshots(&file_path).unwrap();
+        assert_eq!(names.len(), 2);
+        assert!(snapshot_dir.join("a日本語x.json").exists());
+
+        let (name, snapshot) = load_snapshot(&file_path, "2", None).unwrap();
+        assert_eq!(name, names[1]);
+        assert_eq!(snapshot.items.len(), 2);
+        assert_eq!(
+            load_snapshot(&file_path, &names[0], None).unwrap().1.items,
+            list.items
+        );
+        assert!(load_snapshot(&file_path, "3", None).is_err());
+        assert!(load_snapshot(&file_path, "a日本語x", None).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn diff_lists() {
+        let mut old = TodoList::new();
+        for title in ["write docs", "fix login", "plan release"] {
+            old.add(None, Task::new(title)).unwrap();
+        }
+        old.items[2].done = true;
+        let mut new = old.clone();
+        new.items[1].title = String::from("fix login page");
+        new.items[1].done = true;
+        new.items[2].done = false;
+        new.del(Some(Position::AtIndex(0))).unwrap();
+        new.add(None, Task::new("ship it")).unwrap();
+        assert_eq!(
+            diff(&old, &new),
+            vec![
+                Change::Renamed(1, String::from("fix login"), String::from("fix login page")),
+                Change::Checked(1, String::from("fix login page")),
+                Change::Unchecked(2, String::from("plan release")),
+                Change::Added(3, String::from("ship it")),
+                Change::Removed(String::from("write docs")),
+            ]
+        );
+        assert!(diff(&new, &new).is_empty());
+    }
+}
@@ -0,0 +1,251 @@
+use crate::i18n::tr;
+use crate::todo::*;
+use crate::workflow::Workflow;
+
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use serde::Serialize;
+use std::fmt::Write;
+
+const BAR_WIDTH: usize = 40;
+
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Count {
+    pub key: String,
+    pub count: usize,
+}
+
+// the tasks created and done on a day, and the tasks still open at its end
+#[derive(Serialize, PartialEq, Debug)]
+pub struct Day {
+    pub date: NaiveDate,
+    pub created: usize,
+    pub done: usize,
+    pub open: usize,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Stats {
+    pub total: usize,
+    pub done: usize,
+    pub completion_rate: f64,
+    pub avg_hours_to_done: Option<f64>,
+    pub by_state: Vec<Count>,
+    pub by_tag: Vec<Count>,
+    pub by_priority: Vec<Count>,
+    pub days: Vec<Day>,
+}
+
+// tasks from files without timestamps are counted as created before the range,
+// and done tasks without done_at as done before the range
+pub fn compute(list: &TodoList, workflow: &Workflow, from: NaiveDate, to: NaiveDate) -> Stats {
+    let total = list.items.len();
+    let done = list.items.iter().filter(|x| x.done).count();
+
+    let by_state = workflow
+        .states
+        .iter()
+        .map(|state| Count {
+            key: state.clone(),
+            count: count(list, |x| workflow.state_of(x) == state),
+        })
+        .collect();
+
+    let mut by_tag: Vec<Count> = vec![];
+    for task in list.items.iter() {
+        for tag in task.tags() {
+            match by_tag.iter_mut().find(|x| x.key == tag) {
+                Some(row) => row.count += 1,
+                None => by_tag.push(Count {
+                    key: tag.to_string(),
+                    count: 1,
+                }),
+            }
+        }
+    }
+
+    let by_priority = Priority::ALL
+        .iter()
+        .map(|priority| Count {
+            key: priority.name().to_string(),
+            count: count(list, |x| x.priority == *priority),
+        })
+        .collect();
+
+    let durations: Vec<Duration> = list
+        .items
+        .iter()
+        .filter_map(|x| Some(x.done_at? - x.created?))
+        .collect();
+    let avg_hours_to_done = match durations.len() {
+        0 => None,
+        n => Some(durations.iter().map(|x| x.num_minutes()).sum::<i64>() as f64 / 60.0 / n as f64),
+    };
+
+    let on =
+        |date: Option<DateTime<Local>>, day: NaiveDate| date.map(|x| x.date_naive()) == Some(day);
+    let mut days = vec![];
+    let mut day = from;
+    while day <= to {
+        days.push(Day {
+            date: day,
+            created: count(list, |x| on(x.created, day)),
+            done: count(list, |x| x.done && on(x.done_at, day)),
+            open: count(list, |x| is_open_at(x, day)),
+        });
+        day += Duration::days(1);
+    }
+
+    Stats {
+        total,
+        done,
+        completion_rate: if total == 0 {
+            0.0
+        } else {
+            done as f64 / total as f64
+        },
+        avg_hours_to_done,
+        by_state,
+        by_tag,
+        by_priority,
+        days,
+    }
+}
+
+fn count(list: &TodoList, filter: impl Fn(&Task) -> bool) -> usize {
+    list.items.iter().filter(|x| filter(x)).count()
+}
+
+// whether the task was created and not done yet at the end of the day
+fn is_open_at(task: &Task, day: NaiveDate) -> bool {
+    let created = match task.created {
+        Some(created) => created.date_naive() <= day,
+        None => true,
+    };
+    let done = match (task.done, task.done_at) {
+        (false, _) => false,
+        (true, None) => true,
+        (true, Some(done_at)) => done_at.date_naive() <= day,
+    };
+    created && !done
+}
+
+pub fn format_stats(stats: &Stats) -> String {
+    let mut buf = String::new();
+    let rate = format!("{:.0}", stats.completion_rate * 100.0);
+    let total = tr!(
+        "stats_total",
+        total = stats.total,
+        done = stats.done,
+        rate = rate
+    );
+    writeln!(buf, "{}", total).unwrap();
+    if let Some(hours) = stats.avg_hours_to_done {
+        writeln!(buf, "{}", tr!("stats_average", time = format_hours(hours))).unwrap();
+    }
+    writeln!(buf).unwrap();
+    let counts = format_counts(&stats.by_state, "");
+    writeln!(buf, "{}", tr!("stats_by_state", counts = counts)).unwrap();
+    if !stats.by_tag.is_empty() {
+        let counts = format_counts(&stats.by_tag, "#");
+        writeln!(buf, "{}", tr!("stats_by_tag", counts = counts)).unwrap();
+    }
+    let counts = format_counts(&stats.by_priority, "");
+    writeln!(buf, "{}", tr!("stats_by_priority", counts = counts)).unwrap();
+
+    if let (Some(first), Some(last)) = (stats.days.first(), stats.days.last()) {
+        writeln!(buf).unwrap();
+        let burndown = tr!("stats_burndown", from = first.date, to = last.date);
+        writeln!(buf, "{}", burndown).unwrap();
+        let max = stats.days.iter().map(|x| x.open).max().unwrap_or(0).max(1);
+        for day in stats.days.iter() {
+            let bar = "#".repeat((day.open * BAR_WIDTH).div_ceil(max));
+            writeln!(
+                buf,
+                "{}  {:<width$} {:>3}  +{} -{}",
+                day.date.format("%m-%d %a"),
+                bar,
+                day.open,
+                day.created,
+                day.done,
+                width = BAR_WIDTH
+            )
+            .unwrap();
+        }
+    }
+    buf.trim_end().to_string()
+}
+
+fn format_counts(counts: &[Count], prefix: &str) -> String {
+    let counts: Vec<_> = counts
+        .iter()
+        .map(|x| format!("{}{} {}", prefix, x.key, x.count))
+        .collect();
+    counts.join(", ")
+}
+
+fn format_hours(hours: f64) -> String {
+    let hours = hours.round() as i64;
+    match hours {
+        0..=23 => format!("{}h", hours),
+        _ => format!("{}d {}h", hours / 24, hours % 24),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32) -> Option<DateTime<Local>> {
+        Local.with_ymd_and_hms(2024, 3, day, hour, 0, 0).earliest()
+    }
+
+    #[test]
+    fn compute_stats() {
+        let workflow = Workflow::default();
+        let mut list = TodoList::new();
+        for (title, created, done_at) in [
+            ("A #work", at(1, 9), at(2, 9)),
+            ("B #work #ops", at(1, 9), None),
+            ("C", at(2, 9), at(3, 21)),
+            ("D", None, None),
+        ] {
+            let mut task = Task::new(title);
+            task.created = created;
+            task.done_at = done_at;
+            task.done = done_at.is_some();
+            list.add(None, task).unwrap();
+        }
+        list.items[1].priority = Priority::High;
+        // added before tasks had timestamps
+        list.items[3].created = None;
+
+        let from = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 3, 3).unwrap();
+        let stats = compute(&list, &workflow, from, to);
+        assert_eq!(stats.done, 2);
+        assert_eq!(stats.avg_hours_to_done, Some(30.0));
+        assert_eq!(
+            stats.by_tag,
+            vec![
+                Count {
+                    key: String::from("work"),
+                    count: 2
+                },
+                Count {
+                    key: String::from("ops"),
+                    count: 1
+                },
+            ]
+        );
+        assert_eq!(stats.by_priority[0].count, 1);
+
+        let open: Vec<_> = stats
+            .days
+            .iter()
+            .map(|x| (x.created, x.done, x.open))
+            .collect();
+        assert_eq!(open, vec![(2, 0, 3), (1, 1, 3), (0, 1, 2)]);
+        assert!(format_stats(&stats).contains("burndown 2024-03-01 .. 2024-03-03"));
+    }
+}
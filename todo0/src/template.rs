@@ -0,0 +1,228 @@
+use crate::crypt::*;
+use crate::file::write_file;
+use crate::i18n::tr;
+use crate::todo::*;
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path;
+
+// a task of a template, variables are written as {{name}} in the title
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct TemplateTask {
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Priority::is_none")]
+    pub priority: Priority,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct Template {
+    pub tasks: Vec<TemplateTask>,
+}
+
+pub type Templates = BTreeMap<String, Template>;
+
+// the templates are kept next to the list, e.g. todo.json -> todo.templates.json
+pub fn get_templates_path(file_path: &path::Path) -> path::PathBuf {
+    let stem = file_path
+        .file_stem()
+        .map(|x| x.to_string_lossy().to_string())
+        .unwrap_or_default();
+    file_path.with_file_name(format!("{}.templates.json", stem))
+}
+
+// the templates are encrypted like the list, so an encrypted file requires
+// the secret
+pub fn load_templates(
+    file_path: &path::Path,
+    secret: Option<&Secret>,
+) -> Result<Templates, String> {
+    let mut content = match fs::read(file_path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Templates::new()),
+        Err(err) => {
+            return Err(tr!(
+                "read_file_failed",
+                file = format!("{:?}", file_path),
+                error = err
+            ))
+        }
+    };
+    if is_encrypted(&content) {
+        let secret = secret.ok_or(tr!("file_encrypted", file = format!("{:?}", file_path)))?;
+        content = decrypt(secret, &content).map_err(|err| {
+            tr!(
+                "decrypt_file_failed",
+                file = format!("{:?}", file_path),
+                error = err
+            )
+        })?;
+    }
+    serde_json::from_slice(&content).map_err(|err| {
+        tr!(
+            "decode_json_failed",
+            file = format!("{:?}", file_path),
+            error = err
+        )
+    })
+}
+
+pub fn save_templates(
+    file_path: &path::Path,
+    templates: &Templates,
+    secret: Option<&Secret>,
+) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(templates)
+        .map_err(|err| tr!("encode_json_failed", error = err))?;
+    let content = match secret {
+        None => content.into_bytes(),
+        Some(secret) => encrypt(secret, content.as_bytes())?,
+    };
+    write_file(file_path, &content)
+}
+
+// write the templates again with the new secret, when the file is encrypted
+// or decrypted
+pub fn reencrypt_templates(
+    file_path: &path::Path,
+    from: Option<&Secret>,
+    to: Option<&Secret>,
+) -> Result<(), String> {
+    let templates_path = get_templates_path(file_path);
+    if !templates_path.exists() {
+        return Ok(());
+    }
+    // the templates may already be written with the new secret
+    let templates = load_templates(&templates_path, from.or(to))?;
+    save_templates(&templates_path, &templates, to)
+}
+
+// capture the tasks at the 1-based indexes, the values of the vars in the
+// titles are replaced by their {{name}}
+pub fn capture(list: &TodoList, indexes: &[usize], vars: &[(String, String)]) -> Template {
+    let tasks = indexes
+        .iter()
+        .map(|idx| {
+            let task = &list.items[idx - 1];
+            let mut title = task.title.clone();
+            for (name, value) in vars {
+                title = title.replace(value, &format!("{{{{{}}}}}", name));
+            }
+            TemplateTask {
+                title,
+                priority: task.priority,
+                assignee: task.assignee.clone(),
+            }
+        })
+        .collect();
+    Template { tasks }
+}
+
+// new tasks for the template with the vars substituted, all vars used in the
+// titles must be given
+pub fn instantiate(template: &Template, vars: &[(String, String)]) -> Result<Vec<Task>, String> {
+    let mut tasks = vec![];
+    for item in template.tasks.iter() {
+        let title = substitute(&item.title, vars)?;
+        tasks.push(Task {
+            priority: item.priority,
+            assignee: item.assignee.clone(),
+            ..Task::new(&title)
+        });
+    }
+    Ok(tasks)
+}
+
+fn substitute(text: &str, vars: &[(String, String)]) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let end = rest[start..]
+            .find("}}")
+            .ok_or(tr!("unclosed_variable", text = format!("{:?}", text)))?;
+        let name = rest[start + 2..start + end].trim();
+        let value = vars
+            .iter()
+            .find(|(x, _)| x == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| tr!("missing_var", name = name, text = format!("{:?}", text)))?;
+        out.push_str(&rest[..start]);
+        out.push_str(value);
+        rest = &rest[start + end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+// "version=1.2" -> ("version", "1.2"), an empty value would match between
+// every two characters of a title
+pub fn parse_var(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((name, value)) if !name.is_empty() && !value.is_empty() => {
+            Ok((name.to_string(), value.to_string()))
+        }
+        _ => Err(tr!("invalid_variable", var = format!("{:?}", arg))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_and_instantiate() {
+        let mut list = TodoList::new();
+        for title in ["other", "tag v1.2", "publish v1.2 to crates.io", "announce"] {
+            list.add(None, Task::new(title)).unwrap();
+        }
+        list.items[3].priority = Priority::High;
+        let vars = vec![parse_var("version=1.2").unwrap()];
+        let template = capture(&list, &[2, 3, 4], &vars);
+        assert_eq!(template.tasks[0].title, "tag v{{version}}");
+
+        let vars = vec![parse_var("version=2.0").unwrap()];
+        let tasks = instantiate(&template, &vars).unwrap();
+        let titles: Vec<_> = tasks.iter().map(|x| x.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["tag v2.0", "publish v2.0 to crates.io", "announce"]
+        );
+        assert_eq!(tasks[2].priority, Priority::High);
+
+        let err = instantiate(&template, &[]).unwrap_err();
+        assert!(err.contains("missing --var version"), "{}", err);
+        assert!(parse_var("version").is_err());
+        assert!(parse_var("version=").is_err());
+    }
+
+    #[test]
+    fn encrypted_templates() {
+        let dir = std::env::temp_dir().join(format!("todo0-test-{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        let file_path = dir.join("todo.json");
+        let templates_path = get_templates_path(&file_path);
+        let mut templates = Templates::new();
+        let task = TemplateTask {
+            title: String::from("tag v{{version}}"),
+            priority: Priority::None,
+            assignee: None,
+        };
+        templates.insert(String::from("release"), Template { tasks: vec![task] });
+        save_templates(&templates_path, &templates, None).unwrap();
+
+        let secret = Secret::Passphrase(String::from("hunter2"));
+        reencrypt_templates(&file_path, None, Some(&secret)).unwrap();
+        let content = fs::read(&templates_path).unwrap();
+        assert!(is_encrypted(&content));
+        assert!(load_templates(&templates_path, None).is_err());
+        assert_eq!(
+            load_templates(&templates_path, Some(&secret)).unwrap(),
+            templates
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,133 @@
+use std::io::{IsTerminal, Write};
+use std::process::{Child, Command, Stdio};
+use unicode_width::UnicodeWidthStr;
+
+// the width of the text in terminal columns, wide characters like emoji take 2
+pub fn width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+// cut the text to the width, the last column is "…" when it is cut
+pub fn truncate(text: &str, max: usize) -> String {
+    if width(text) <= max {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    for c in text.chars() {
+        let mut next = out.clone();
+        next.push(c);
+        if width(&next) > max.saturating_sub(1) {
+            break;
+        }
+        out = next;
+    }
+    out.push('…');
+    out
+}
+
+pub fn pad(text: &str, max: usize) -> String {
+    format!("{}{}", text, " ".repeat(max.saturating_sub(width(text))))
+}
+
+// split the text into lines of at most the width, at spaces where possible
+pub fn wrap(text: &str, max: usize) -> Vec<String> {
+    let max = max.max(1);
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let sep = if line.is_empty() { "" } else { " " };
+        if width(&line) + sep.len() + width(word) <= max {
+            line.push_str(sep);
+            line.push_str(word);
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        // a word longer than the line is split
+        for c in word.chars() {
+            if width(&line) + width(c.encode_utf8(&mut [0; 4])) > max && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            line.push(c);
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+// the width to render for, COLUMNS overrides the terminal width. None when
+// the output is not a terminal, e.g. piped into another command.
+pub fn output_width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS").ok().and_then(|x| x.parse().ok()) {
+        return Some(columns);
+    }
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    terminal_size::terminal_size().map(|(w, _)| w.0 as usize)
+}
+
+// print the text, through $PAGER (or "less -R") when it does not fit on the
+// screen. the text is printed directly if the pager can not be started.
+pub fn print_paged(text: &str) {
+    let height = match terminal_size::terminal_size() {
+        Some((_, h)) if std::io::stdout().is_terminal() => h.0 as usize,
+        _ => usize::MAX,
+    };
+    if text.lines().count() < height {
+        println!("{}", text);
+        return;
+    }
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|x| !x.trim().is_empty())
+        .unwrap_or(String::from("less -R"));
+    let mut child = match spawn_pager(&pager) {
+        Ok(child) => child,
+        Err(_) => {
+            println!("{}", text);
+            return;
+        }
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // the pager may quit before reading everything
+        let _ = writeln!(stdin, "{}", text);
+    }
+    let _ = child.wait();
+}
+
+// the pager is run without a shell, so that spawning fails when the program
+// does not exist, e.g. "less -R" runs less with the argument -R
+fn spawn_pager(pager: &str) -> std::io::Result<Child> {
+    let mut words = pager.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    Command::new(program)
+        .args(words)
+        .stdin(Stdio::piped())
+        .spawn()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_pager() {
+        assert!(spawn_pager("todo0-no-such-pager -R").is_err());
+    }
+
+    #[test]
+    fn wide_characters() {
+        assert_eq!(width("日本"), 4);
+        assert_eq!(truncate("日本語のタスク", 7), "日本語…");
+        assert_eq!(pad("日本", 6), "日本  ");
+        assert_eq!(
+            wrap("write the 日本語 docs", 9),
+            vec!["write the", "日本語", "docs"]
+        );
+        assert_eq!(wrap("abcdefgh", 3), vec!["abc", "def", "gh"]);
+    }
+}
@@ -1,7 +1,12 @@
+use crate::date::format_due;
+use crate::i18n::tr;
+use crate::term;
+
+use chrono::{DateTime, Local};
 use colored::*;
 use rand;
 use serde::{Deserialize, Serialize};
-use std::fmt::{self, Write};
+use std::fmt;
 
 #[derive(PartialEq, Debug)]
 pub enum ActionResult {
@@ -19,7 +24,7 @@ pub enum Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::NotFound => f.write_str("task not found"),
+            Self::NotFound => f.write_str(&tr!("task_not_found")),
             Self::Validation(s) => f.write_str(s),
         }
     }
@@ -31,28 +36,106 @@ pub enum Position {
     ById(TaskId),
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Debug, Default)]
 pub struct TaskId(pub u64);
 
-#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
 pub struct Task {
     pub id: TaskId,
     pub title: String,
     pub done: bool,
+    // the workflow state, see workflow::Workflow::state_of
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub state: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub intervals: Vec<Interval>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by: Vec<TaskId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<DateTime<Local>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    #[serde(default, skip_serializing_if = "Priority::is_none")]
+    pub priority: Priority,
+    // set when the task is added to the list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<DateTime<Local>>,
+    // set when the task is moved to the done state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub done_at: Option<DateTime<Local>>,
+    // the comment the task was found in by "scan"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+    // an iCalendar RRULE value like "FREQ=WEEKLY;BYDAY=MO", kept for import and export
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<String>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+}
+
+// a location in a source file, the path is relative to the scanned dir
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Source {
+    pub file: String,
+    pub line: usize,
+}
+
+// a period of work on a task, end is None while the timer is running
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Interval {
+    pub start: DateTime<Local>,
+    pub end: Option<DateTime<Local>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct TodoList {
     pub items: Vec<Task>,
 }
 
+#[derive(Default)]
 pub struct FormatOptions {
     pub use_color: bool,
+    // the width to fit the lines in, long titles are wrapped. None for no limit
+    pub width: Option<usize>,
+    // cut long titles instead of wrapping them
+    pub truncate: bool,
 }
 
-impl Default for FormatOptions {
-    fn default() -> Self {
-        Self { use_color: false }
+// titles are not wrapped narrower than this, even if the metadata does not fit
+const MIN_TITLE_WIDTH: usize = 20;
+
+impl Priority {
+    pub const ALL: [Priority; 4] = [Self::High, Self::Medium, Self::Low, Self::None];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "high" | "h" => Some(Self::High),
+            "medium" | "med" | "m" => Some(Self::Medium),
+            "low" | "l" => Some(Self::Low),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::None
     }
 }
 
@@ -68,15 +151,28 @@ impl Task {
         Task {
             id: TaskId::new(),
             title: title.to_string(),
-            done: false,
+            ..Default::default()
         }
     }
 
+    // words in the title starting with "#", e.g. "fix login #work #urgent"
+    pub fn tags(&self) -> Vec<&str> {
+        self.title
+            .split_whitespace()
+            .filter_map(|word| word.strip_prefix('#'))
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.intervals.iter().any(|x| x.end.is_none())
+    }
+
     pub fn validate(task: &Self) -> Result<Self, Error> {
         // validate
         let title = task.title.trim();
         if title == "" {
-            return Err(Error::Validation(String::from("title is empty")));
+            return Err(Error::Validation(tr!("title_empty")));
         }
 
         // generate id
@@ -93,7 +189,7 @@ impl Task {
 
 impl fmt::Display for TodoList {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let format_str = self.format(FormatOptions { use_color: false });
+        let format_str = self.format(FormatOptions::default());
         f.write_str(&format_str).unwrap();
         Ok(())
     }
@@ -105,27 +201,96 @@ impl TodoList {
     }
 
     pub fn format(&self, opts: FormatOptions) -> String {
-        if self.items.len() == 0 {
-            return String::from("no items");
+        self.format_filter(opts, |_| true)
+    }
+
+    // format only the tasks matching the filter, keeping their positions in the list
+    pub fn format_filter(&self, opts: FormatOptions, filter: impl Fn(&Task) -> bool) -> String {
+        let items: Vec<_> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| filter(task))
+            .collect();
+        if items.is_empty() {
+            return tr!("no_items");
         }
 
-        let mut buf = String::new();
-        self.items.iter().enumerate().for_each(|(idx, task)| {
-            if idx > 0 {
-                buf.write_str("\n").unwrap();
+        // the metadata columns: source, assignee, due date and check mark
+        let rows: Vec<[String; 4]> = items
+            .iter()
+            .map(|(_, task)| {
+                [
+                    match &task.source {
+                        Some(source) => format!("[{}:{}]", source.file, source.line),
+                        None => String::new(),
+                    },
+                    match &task.assignee {
+                        Some(assignee) => format!("@{}", assignee),
+                        None => String::new(),
+                    },
+                    match task.due {
+                        Some(due) => tr!("due_label", due = format_due(due)),
+                        None => String::new(),
+                    },
+                    String::from(if task.done { "✔️" } else { "" }),
+                ]
+            })
+            .collect();
+        let column_widths: Vec<usize> = (0..4)
+            .map(|col| rows.iter().map(|row| term::width(&row[col])).max().unwrap())
+            .collect();
+        let columns: Vec<usize> = (0..4).filter(|col| column_widths[*col] > 0).collect();
+        let meta_width: usize = columns.iter().map(|col| column_widths[*col] + 2).sum();
+
+        let index_width = (items[items.len() - 1].0 + 1).to_string().len();
+        let prefix_width = index_width + 2;
+        let max_title = items
+            .iter()
+            .map(|(_, x)| term::width(&x.title))
+            .max()
+            .unwrap();
+        let title_width = match opts.width {
+            None => max_title,
+            Some(width) => {
+                let available = width.saturating_sub(prefix_width + meta_width);
+                max_title.min(available.max(MIN_TITLE_WIDTH))
             }
-            let check = if task.done { "  ✔️" } else { "" };
-            let line_str = format!("{:>3}. {}{}", idx + 1, task.title, check);
-            if opts.use_color && task.done {
-                buf.write_fmt(format_args!("{}", &line_str.black()))
-                    .unwrap();
+        };
+
+        let mut lines = vec![];
+        for ((idx, task), row) in items.iter().zip(rows.iter()) {
+            let title_lines = if term::width(&task.title) <= title_width {
+                vec![task.title.clone()]
+            } else if opts.truncate {
+                vec![term::truncate(&task.title, title_width)]
             } else {
-                buf.write_fmt(format_args!("{}", &line_str.yellow()))
-                    .unwrap();
+                term::wrap(&task.title, title_width)
+            };
+            let meta: Vec<_> = columns
+                .iter()
+                .map(|col| term::pad(&row[*col], column_widths[*col]))
+                .collect();
+            let first = format!(
+                "{:>width$}. {}  {}",
+                idx + 1,
+                term::pad(&title_lines[0], title_width),
+                meta.join("  "),
+                width = index_width
+            );
+            let mut task_lines = vec![first.trim_end().to_string()];
+            for line in title_lines.iter().skip(1) {
+                task_lines.push(format!("{}{}", " ".repeat(prefix_width), line));
+            }
+            for line in task_lines {
+                lines.push(match (opts.use_color, task.done) {
+                    (false, _) => line,
+                    (true, true) => line.black().to_string(),
+                    (true, false) => line.yellow().to_string(),
+                });
             }
-        });
-        dbg!(&buf);
-        buf
+        }
+        lines.join("\n")
     }
 
     // add the task to the list (if the task.id already exist in the list, update it instead)
@@ -142,8 +307,17 @@ impl TodoList {
             return Ok(result);
         }
 
-        // add the task at the requested position
+        // add the task at the requested position, which may be right after the end
+        if let Some(Position::AtIndex(index)) = pos {
+            if index > self.items.len() {
+                return Err(Error::NotFound);
+            }
+        }
         let id = task.id;
+        let task = Task {
+            created: task.created.or_else(|| Some(Local::now())),
+            ..task
+        };
         match pos {
             None => self.items.insert(self.items.len(), task),
             Some(Position::AtIndex(index)) => self.items.insert(index, task),
@@ -198,7 +372,7 @@ impl TodoList {
 
     fn get_index(&mut self, pos: Option<Position>) -> Result<(usize, TaskId), Error> {
         match pos {
-            None => Err(Error::Validation(String::from("invalid position"))),
+            None => Err(Error::Validation(tr!("invalid_position"))),
             Some(Position::AtIndex(index)) => {
                 if index >= self.items.len() {
                     return Err(Error::NotFound);
@@ -249,8 +423,8 @@ impl TodoList {
             .iter()
             .enumerate()
             .filter_map(|(idx, task)| match indexes.contains(&idx) {
-                true => Some(task.clone()),
-                false => None,
+                true => None,
+                false => Some(task.clone()),
             })
             .collect();
 
@@ -262,6 +436,7 @@ impl TodoList {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     fn get_tasks<'a>(list: &'a TodoList) -> Vec<&'a str> {
         list.items.iter().map(|x| x.title.as_str()).collect()
@@ -322,6 +497,7 @@ mod tests {
             id: task_id,
             title: String::from("B0"),
             done: true,
+            ..Default::default()
         };
         let res = list.edit(None, task);
         assert_eq!(res.unwrap(), ActionResult::Updated(task_id));
@@ -331,7 +507,8 @@ mod tests {
             Task {
                 id: task_id,
                 title: String::from("B0"),
-                done: true
+                done: true,
+                ..Default::default()
             }
         );
 
@@ -348,11 +525,61 @@ mod tests {
             Task {
                 id: task_id,
                 title: String::from("C0"),
-                done: true
+                done: true,
+                ..Default::default()
             }
         );
     }
 
+    #[test]
+    fn format_tasks() {
+        let mut list = TodoList::new();
+        list.add(None, Task::new("A")).unwrap();
+        list.add(None, Task::new("B")).unwrap();
+        list.items[0].assignee = Some(String::from("alice"));
+        list.items[1].done = true;
+        assert_eq!(
+            list.format(FormatOptions::default()),
+            "1. A  @alice\n2. B          ✔️"
+        );
+        assert_eq!(
+            list.format_filter(FormatOptions::default(), |x| x.done),
+            "2. B  ✔️"
+        );
+    }
+
+    #[test]
+    fn format_to_width() {
+        let mut list = TodoList::new();
+        list.add(None, Task::new("write the release notes for 日本語 users"))
+            .unwrap();
+        list.add(None, Task::new("short")).unwrap();
+        list.items[1].assignee = Some(String::from("bob"));
+        let opts = FormatOptions {
+            width: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(
+            list.format(opts),
+            [
+                "1. write the release",
+                "   notes for 日本語",
+                "   users",
+                "2. short                  @bob",
+            ]
+            .join("\n")
+        );
+        let opts = FormatOptions {
+            width: Some(30),
+            truncate: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            list.format(opts),
+            "1. write the release no…\n2. short                  @bob"
+        );
+    }
+
     #[test]
     fn delete_task() {
         let mut list = TodoList::new();
@@ -374,4 +601,121 @@ mod tests {
         assert_eq!(res.unwrap(), ActionResult::Deleted(task_id));
         assert_eq!(get_tasks(&list), vec!["A", "D"]);
     }
+
+    #[test]
+    fn delete_tasks() {
+        let mut list = TodoList::new();
+        for title in ["A", "B", "C", "D"] {
+            list.add(None, Task::new(title)).unwrap();
+        }
+        let task_id = list.items[3].id;
+        let res = list.delete(&[
+            Some(Position::AtIndex(1)),
+            Some(Position::ById(task_id)),
+            Some(Position::AtIndex(9)),
+        ]);
+        assert_eq!(res[1], Ok(ActionResult::Deleted(task_id)));
+        assert_eq!(res[2], Err(Error::NotFound));
+        assert_eq!(get_tasks(&list), vec!["A", "C"]);
+    }
+
+    // the operations on a list, indexes may be out of range
+    #[derive(Clone, Debug)]
+    enum Op {
+        Add(Option<usize>, String),
+        Edit(usize, String),
+        Del(usize),
+        Delete(Vec<usize>),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        let title = "[a-z]{1,3}";
+        prop_oneof![
+            (proptest::option::of(0..8usize), title).prop_map(|(pos, x)| Op::Add(pos, x)),
+            (0..8usize, title).prop_map(|(idx, x)| Op::Edit(idx, x)),
+            (0..8usize).prop_map(Op::Del),
+            proptest::collection::vec(0..8usize, 0..4).prop_map(Op::Delete),
+        ]
+    }
+
+    // apply the operation to a plain vec of (id, title) as the reference
+    fn apply_model(model: &mut Vec<(TaskId, String)>, op: &Op, new_id: TaskId) {
+        match op {
+            Op::Add(None, title) => model.push((new_id, title.clone())),
+            Op::Add(Some(idx), title) => {
+                if *idx <= model.len() {
+                    model.insert(*idx, (new_id, title.clone()))
+                }
+            }
+            Op::Edit(idx, title) => {
+                if let Some(item) = model.get_mut(*idx) {
+                    item.1 = title.clone();
+                }
+            }
+            Op::Del(idx) => {
+                if *idx < model.len() {
+                    model.remove(*idx);
+                }
+            }
+            Op::Delete(indexes) => {
+                let mut idx = 0;
+                model.retain(|_| {
+                    idx += 1;
+                    !indexes.contains(&(idx - 1))
+                });
+            }
+        }
+    }
+
+    fn apply_list(list: &mut TodoList, op: &Op, task: Task) {
+        match op {
+            Op::Add(pos, _) => {
+                let len = list.items.len();
+                let res = list.add(pos.map(Position::AtIndex), task);
+                match pos {
+                    Some(idx) if *idx > len => assert_eq!(res, Err(Error::NotFound)),
+                    _ => assert!(res.is_ok()),
+                }
+            }
+            Op::Edit(idx, _) => {
+                let res = list.edit(Some(Position::AtIndex(*idx)), task);
+                assert_eq!(res.is_ok(), *idx < list.items.len());
+            }
+            Op::Del(idx) => {
+                let len = list.items.len();
+                let res = list.del(Some(Position::AtIndex(*idx)));
+                assert_eq!(res.is_ok(), *idx < len);
+            }
+            Op::Delete(indexes) => {
+                let len = list.items.len();
+                let positions: Vec<_> = indexes
+                    .iter()
+                    .map(|x| Some(Position::AtIndex(*x)))
+                    .collect();
+                let res = list.delete(&positions);
+                for (idx, res) in indexes.iter().zip(res) {
+                    assert_eq!(res.is_ok(), *idx < len);
+                }
+            }
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn operations_match_model(ops in proptest::collection::vec(op(), 0..40)) {
+            let mut list = TodoList::new();
+            let mut model = vec![];
+            for op in ops.iter() {
+                let title = match op {
+                    Op::Add(_, title) | Op::Edit(_, title) => title.as_str(),
+                    _ => "unused",
+                };
+                let task = Task::new(title);
+                apply_model(&mut model, op, task.id);
+                apply_list(&mut list, op, task);
+                let items: Vec<_> = list.items.iter().map(|x| (x.id, x.title.clone())).collect();
+                prop_assert_eq!(&items, &model, "after {:?}", op);
+            }
+        }
+    }
 }
@@ -0,0 +1,187 @@
+use crate::i18n::tr;
+use crate::todo::*;
+
+use chrono::{DateTime, Duration, Local};
+use std::fmt::Write;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GroupBy {
+    Task,
+    Tag,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ReportRow {
+    pub key: String,
+    pub duration: Duration,
+}
+
+// start the timer on the task at the index, the running timer is stopped first
+// because only one task can be tracked at a time. returns the stopped task.
+pub fn start(
+    list: &mut TodoList,
+    index: usize,
+    now: DateTime<Local>,
+) -> Result<Option<Task>, Error> {
+    let task = list.items.get(index).ok_or(Error::NotFound)?;
+    if task.is_running() {
+        return Err(Error::Validation(tr!(
+            "already_started",
+            title = task.title
+        )));
+    }
+    let id = task.id;
+    let stopped = stop(list, now)?;
+
+    let mut task = list.items[index].clone();
+    task.intervals.push(Interval {
+        start: now,
+        end: None,
+    });
+    list.edit(Some(Position::ById(id)), task)?;
+    Ok(stopped)
+}
+
+// stop the running timer, returns the stopped task if there was one
+pub fn stop(list: &mut TodoList, now: DateTime<Local>) -> Result<Option<Task>, Error> {
+    let task = list.items.iter().find(|x| x.is_running());
+    let mut task = match task {
+        None => return Ok(None),
+        Some(task) => task.clone(),
+    };
+    for interval in task.intervals.iter_mut() {
+        if interval.end.is_none() {
+            interval.end = Some(now);
+        }
+    }
+    list.edit(Some(Position::ById(task.id)), task.clone())?;
+    Ok(Some(task))
+}
+
+// tracked time of the task after since, a running interval counts until now
+pub fn tracked(task: &Task, since: Option<DateTime<Local>>, now: DateTime<Local>) -> Duration {
+    task.intervals
+        .iter()
+        .map(|x| {
+            let start = match since {
+                Some(since) if since > x.start => since,
+                _ => x.start,
+            };
+            let end = x.end.unwrap_or(now);
+            if end > start {
+                end - start
+            } else {
+                Duration::zero()
+            }
+        })
+        .sum()
+}
+
+// sum the tracked time by task or by tag, rows are in list order. a task with
+// several tags is counted in each of them.
+pub fn report(
+    list: &TodoList,
+    since: Option<DateTime<Local>>,
+    by: GroupBy,
+    now: DateTime<Local>,
+) -> Vec<ReportRow> {
+    let mut rows: Vec<ReportRow> = vec![];
+    for task in list.items.iter() {
+        let duration = tracked(task, since, now);
+        if duration.is_zero() {
+            continue;
+        }
+        let keys = match by {
+            GroupBy::Task => vec![task.title.clone()],
+            GroupBy::Tag => match task.tags() {
+                tags if tags.is_empty() => vec![tr!("untagged")],
+                tags => tags.iter().map(|tag| format!("#{}", tag)).collect(),
+            },
+        };
+        for key in keys {
+            match rows.iter_mut().find(|row| row.key == key) {
+                Some(row) => row.duration += duration,
+                None => rows.push(ReportRow { key, duration }),
+            }
+        }
+    }
+    rows
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.num_minutes();
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+pub fn format_report(rows: &[ReportRow], total: Duration) -> String {
+    let mut buf = String::new();
+    for row in rows {
+        writeln!(buf, "{:>8}  {}", format_duration(row.duration), row.key).unwrap();
+    }
+    let duration = format!("{:>8}", format_duration(total));
+    write!(buf, "{}", tr!("report_total", duration = duration)).unwrap();
+    buf
+}
+
+pub fn format_report_csv(rows: &[ReportRow], by: GroupBy) -> String {
+    let mut buf = String::new();
+    let column = match by {
+        GroupBy::Task => "task",
+        GroupBy::Tag => "tag",
+    };
+    writeln!(buf, "{},seconds,hours", column).unwrap();
+    for row in rows {
+        let seconds = row.duration.num_seconds();
+        writeln!(
+            buf,
+            "\"{}\",{},{:.2}",
+            row.key.replace('"', "\"\""),
+            seconds,
+            seconds as f64 / 3600.0
+        )
+        .unwrap();
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, min: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 1, hour, min, 0).unwrap()
+    }
+
+    #[test]
+    fn track_and_report() {
+        let mut list = TodoList::new();
+        list.add(None, Task::new("A #work")).unwrap();
+        list.add(None, Task::new("B #work #ops")).unwrap();
+        list.add(None, Task::new("C")).unwrap();
+
+        assert_eq!(start(&mut list, 0, at(9, 0)).unwrap(), None);
+        let stopped = start(&mut list, 1, at(10, 0)).unwrap().unwrap();
+        assert_eq!(stopped.title, "A #work");
+        assert!(list.items[1].is_running());
+        assert!(start(&mut list, 1, at(10, 5)).is_err());
+
+        stop(&mut list, at(10, 30)).unwrap();
+        assert_eq!(stop(&mut list, at(10, 40)).unwrap(), None);
+        start(&mut list, 2, at(11, 0)).unwrap();
+
+        let rows = report(&list, None, GroupBy::Tag, at(11, 15));
+        let rows: Vec<_> = rows
+            .iter()
+            .map(|row| (row.key.as_str(), row.duration.num_minutes()))
+            .collect();
+        assert_eq!(rows, vec![("#work", 90), ("#ops", 30), ("(untagged)", 15)]);
+
+        let rows = report(&list, Some(at(9, 30)), GroupBy::Task, at(11, 15));
+        let rows: Vec<_> = rows
+            .iter()
+            .map(|row| (row.key.as_str(), row.duration.num_minutes()))
+            .collect();
+        assert_eq!(rows, vec![("A #work", 30), ("B #work #ops", 30), ("C", 15)]);
+    }
+}
@@ -0,0 +1,284 @@
+use crate::deps::*;
+use crate::i18n::tr;
+use crate::term;
+use crate::todo::*;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// the weights of the urgency score, set as "urgency" in the config, e.g.
+//   "urgency": { "due": 15.0, "tags": { "urgent": 5.0, "someday": -3.0 } }
+// the due and age weights are scaled by how close the due date is and by how
+// old the task is, the other weights are added as they are.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct Coefficients {
+    pub priority_high: f64,
+    pub priority_medium: f64,
+    pub priority_low: f64,
+    pub due: f64,
+    pub age: f64,
+    // tasks of this age get the full age weight
+    pub max_age_days: f64,
+    // added once for a task with any tag
+    pub tagged: f64,
+    // added for each of these tags
+    pub tags: HashMap<String, f64>,
+    pub blocked: f64,
+    // added for a task which blocks open tasks
+    pub blocking: f64,
+}
+
+impl Default for Coefficients {
+    fn default() -> Self {
+        Self {
+            priority_high: 6.0,
+            priority_medium: 3.9,
+            priority_low: 1.8,
+            due: 12.0,
+            age: 2.0,
+            max_age_days: 365.0,
+            tagged: 1.0,
+            tags: HashMap::new(),
+            blocked: -5.0,
+            blocking: 8.0,
+        }
+    }
+}
+
+// the score of a task and the parts it is made of, e.g. ("due", 9.6)
+#[derive(Debug, PartialEq)]
+pub struct Score {
+    pub total: f64,
+    pub parts: Vec<(&'static str, f64)>,
+}
+
+// done tasks score 0
+pub fn score(list: &TodoList, task: &Task, coeffs: &Coefficients, now: DateTime<Local>) -> Score {
+    let mut parts = vec![];
+    if !task.done {
+        let priority = match task.priority {
+            Priority::High => coeffs.priority_high,
+            Priority::Medium => coeffs.priority_medium,
+            Priority::Low => coeffs.priority_low,
+            Priority::None => 0.0,
+        };
+        parts.push(("priority", priority));
+        if let Some(due) = task.due {
+            let days = (due - now).num_seconds() as f64 / 86400.0;
+            parts.push(("due", coeffs.due * due_factor(days)));
+        }
+        if let Some(created) = task.created {
+            let days = (now - created).num_seconds().max(0) as f64 / 86400.0;
+            let factor = (days / coeffs.max_age_days.max(1.0)).min(1.0);
+            parts.push(("age", coeffs.age * factor));
+        }
+        let tags = task.tags();
+        let mut tagged = if tags.is_empty() { 0.0 } else { coeffs.tagged };
+        for tag in tags {
+            tagged += coeffs.tags.get(tag).copied().unwrap_or(0.0);
+        }
+        parts.push(("tags", tagged));
+        if is_blocked(list, task) {
+            parts.push(("blocked", coeffs.blocked));
+        }
+        let blocking = list
+            .items
+            .iter()
+            .any(|x| !x.done && x.blocked_by.contains(&task.id));
+        if blocking {
+            parts.push(("blocking", coeffs.blocking));
+        }
+    }
+    parts.retain(|(_, value)| *value != 0.0);
+    Score {
+        total: parts.iter().fold(0.0, |sum, (_, value)| sum + value),
+        parts,
+    }
+}
+
+// 1 for tasks overdue by a week or more, 0.2 for tasks due in two weeks or
+// later and linear in between
+fn due_factor(days: f64) -> f64 {
+    if days <= -7.0 {
+        1.0
+    } else if days >= 14.0 {
+        0.2
+    } else {
+        0.2 + 0.8 * (14.0 - days) / 21.0
+    }
+}
+
+// the index of the ready task with the highest score, the first one on ties
+pub fn next(list: &TodoList, coeffs: &Coefficients, now: DateTime<Local>) -> Option<usize> {
+    let mut best: Option<(usize, f64)> = None;
+    for (idx, task) in list.items.iter().enumerate() {
+        if !is_ready(list, task) {
+            continue;
+        }
+        let total = score(list, task, coeffs, now).total;
+        if best.is_none_or(|(_, best)| total > best) {
+            best = Some((idx, total));
+        }
+    }
+    best.map(|(idx, _)| idx)
+}
+
+// "priority 6.0 + due 9.6", parts which round to 0.0 like the age of a new
+// task are left out
+pub fn format_parts(score: &Score) -> String {
+    let parts: Vec<_> = score
+        .parts
+        .iter()
+        .map(|(name, value)| format!("{} {:.1}", name, value))
+        .filter(|x| !x.ends_with(" 0.0") && !x.ends_with(" -0.0"))
+        .collect();
+    parts.join(" + ")
+}
+
+// the tasks at the indexes by score, highest first, with their positions in
+// the list and the parts of the score
+pub fn format_by_urgency(
+    list: &TodoList,
+    indexes: &[usize],
+    coeffs: &Coefficients,
+    now: DateTime<Local>,
+    width: Option<usize>,
+) -> String {
+    if indexes.is_empty() {
+        return tr!("no_items");
+    }
+    let mut rows: Vec<(usize, Score)> = indexes
+        .iter()
+        .map(|idx| (*idx, score(list, &list.items[*idx], coeffs, now)))
+        .collect();
+    // stable, so tasks with the same score keep their order
+    rows.sort_by(|a, b| b.1.total.total_cmp(&a.1.total));
+
+    let index_width = (indexes.iter().max().unwrap() + 1).to_string().len();
+    let scores: Vec<String> = rows
+        .iter()
+        .map(|(_, x)| format!("{:.1}", x.total))
+        .collect();
+    let score_width = scores.iter().map(|x| x.len()).max().unwrap();
+    let parts: Vec<String> = rows.iter().map(|(_, x)| format_parts(x)).collect();
+    let max_title = rows
+        .iter()
+        .map(|(idx, _)| term::width(&list.items[*idx].title))
+        .max()
+        .unwrap();
+    let title_width = match width {
+        None => max_title,
+        Some(width) => {
+            let used = index_width + 2 + score_width + 2 + 2;
+            let parts_width = parts.iter().map(|x| x.len()).max().unwrap();
+            max_title.min(width.saturating_sub(used + parts_width).max(20))
+        }
+    };
+    let mut lines = vec![];
+    for (((idx, _), score), parts) in rows.iter().zip(scores).zip(parts) {
+        let title = term::truncate(&list.items[*idx].title, title_width);
+        let line = format!(
+            "{:>iw$}. {:>sw$}  {}  {}",
+            idx + 1,
+            score,
+            term::pad(&title, title_width),
+            parts,
+            iw = index_width,
+            sw = score_width
+        );
+        lines.push(line.trim_end().to_string());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn now() -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap()
+    }
+
+    fn list() -> TodoList {
+        let mut list = TodoList::new();
+        for title in ["write docs", "fix login #work", "plan release", "done"] {
+            list.add(None, Task::new(title)).unwrap();
+        }
+        list.items[0].created = Some(now() - Duration::days(73));
+        list.items[1].priority = Priority::High;
+        list.items[2].due = Some(now() + Duration::days(1));
+        list.items[3].done = true;
+        list.items[3].priority = Priority::High;
+        list
+    }
+
+    #[test]
+    fn score_parts() {
+        let list = list();
+        let coeffs = Coefficients::default();
+        let parts = |idx: usize| score(&list, &list.items[idx], &coeffs, now()).parts;
+        assert_eq!(parts(0), vec![("age", 0.4)]);
+        assert_eq!(parts(1), vec![("priority", 6.0), ("tags", 1.0)]);
+        // due tomorrow: 0.2 + 0.8 * 13 / 21
+        let due = parts(2)[0].1;
+        assert!((due - 12.0 * (0.2 + 0.8 * 13.0 / 21.0)).abs() < 1e-9);
+        assert!(parts(3).is_empty());
+
+        assert_eq!(due_factor(-30.0), 1.0);
+        assert_eq!(due_factor(-7.0), 1.0);
+        assert_eq!(due_factor(14.0), 0.2);
+        assert_eq!(due_factor(60.0), 0.2);
+    }
+
+    #[test]
+    fn coefficients_from_config() {
+        let mut list = list();
+        let coeffs: Coefficients =
+            serde_json::from_str(r#"{ "due": 1.0, "tags": { "work": -10.0 } }"#).unwrap();
+        assert_eq!(coeffs.priority_high, 6.0);
+        let total =
+            |list: &TodoList, idx: usize| score(list, &list.items[idx], &coeffs, now()).total;
+        assert_eq!(total(&list, 1), 6.0 + 1.0 - 10.0);
+        assert!(total(&list, 2) < 1.0);
+
+        // blocking an open task counts for the blocker, being blocked against it
+        let blocker = list.items[0].id;
+        list.items[2].blocked_by.push(blocker);
+        let parts = score(&list, &list.items[0], &coeffs, now()).parts;
+        assert_eq!(parts.last(), Some(&("blocking", 8.0)));
+        let parts = score(&list, &list.items[2], &coeffs, now()).parts;
+        assert_eq!(parts.last(), Some(&("blocked", -5.0)));
+    }
+
+    #[test]
+    fn next_ready_task() {
+        let mut list = list();
+        let coeffs = Coefficients::default();
+        // the due date outweighs the priority
+        assert_eq!(next(&list, &coeffs, now()), Some(2));
+        // blocked tasks are skipped
+        let blocker = list.items[1].id;
+        list.items[2].blocked_by.push(blocker);
+        assert_eq!(next(&list, &coeffs, now()), Some(1));
+        list.items[1].done = true;
+        list.items[0].done = true;
+        list.items[2].done = true;
+        assert_eq!(next(&list, &coeffs, now()), None);
+    }
+
+    #[test]
+    fn format_sorted() {
+        let list = list();
+        let out = format_by_urgency(&list, &[0, 1, 2, 3], &Coefficients::default(), now(), None);
+        assert_eq!(
+            out,
+            "3. 8.3  plan release     due 8.3
+2. 7.0  fix login #work  priority 6.0 + tags 1.0
+1. 0.4  write docs       age 0.4
+4. 0.0  done"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+// the extism host for the plugins of plugin.rs. plugins run with wasi but
+// without allowed paths or hosts, so they can not touch the filesystem or the
+// network, only the list through the host functions.
+
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::plugin::*;
+use crate::todo::TodoList;
+use crate::workflow::Workflow;
+
+use extism::*;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+// the copy of the list the host functions work on while a plugin runs
+struct HostState {
+    list: TodoList,
+    workflow: Workflow,
+}
+
+host_fn!(todo_items(state: HostState; _input: String) -> String {
+    let state = state.get()?;
+    let state = state.lock().unwrap();
+    api_items(&state.list).map_err(Error::msg)
+});
+
+host_fn!(todo_add(state: HostState; input: String) -> String {
+    let state = state.get()?;
+    let mut state = state.lock().unwrap();
+    api_add(&mut state.list, &input).map_err(Error::msg)
+});
+
+host_fn!(todo_edit(state: HostState; input: String) -> String {
+    let state = state.get()?;
+    let mut state = state.lock().unwrap();
+    let HostState { list, workflow } = &mut *state;
+    api_edit(list, workflow, &input).map_err(Error::msg)
+});
+
+host_fn!(todo_delete(state: HostState; input: String) -> String {
+    let state = state.get()?;
+    let mut state = state.lock().unwrap();
+    api_delete(&mut state.list, &input).map_err(Error::msg)
+});
+
+fn load(path: &Path, state: &UserData<HostState>) -> Result<Plugin, String> {
+    let manifest = Manifest::new([Wasm::file(path)]).with_timeout(PLUGIN_TIMEOUT);
+    PluginBuilder::new(manifest)
+        .with_wasi(true)
+        .with_function("todo_items", [PTR], [PTR], state.clone(), todo_items)
+        .with_function("todo_add", [PTR], [PTR], state.clone(), todo_add)
+        .with_function("todo_edit", [PTR], [PTR], state.clone(), todo_edit)
+        .with_function("todo_delete", [PTR], [PTR], state.clone(), todo_delete)
+        .build()
+        .map_err(|err| {
+            tr!(
+                "load_plugin_failed",
+                plugin = format!("{:?}", path),
+                error = err
+            )
+        })
+}
+
+fn call(plugin: &mut Plugin, path: &Path, name: &str, input: String) -> Result<String, String> {
+    plugin.call::<String, String>(name, input).map_err(|err| {
+        tr!(
+            "plugin_failed",
+            plugin = format!("{:?}", path),
+            function = name,
+            error = err
+        )
+    })
+}
+
+fn describe_plugin(plugin: &mut Plugin, path: &Path) -> Result<PluginInfo, String> {
+    let out = call(plugin, path, "describe", String::new())?;
+    serde_json::from_str(&out).map_err(|err| {
+        tr!(
+            "invalid_describe",
+            plugin = format!("{:?}", path),
+            error = err
+        )
+    })
+}
+
+fn new_state(todo_list: &TodoList, config: &Config) -> UserData<HostState> {
+    UserData::new(HostState {
+        list: todo_list.clone(),
+        workflow: config.workflow.clone(),
+    })
+}
+
+pub fn describe(paths: &[PathBuf]) -> Result<Vec<PluginInfo>, String> {
+    let state = new_state(&TodoList::new(), &Config::default());
+    let mut infos = vec![];
+    for path in paths {
+        let mut plugin = load(path, &state)?;
+        infos.push(describe_plugin(&mut plugin, path)?);
+    }
+    Ok(infos)
+}
+
+// the list is only replaced when the command succeeds
+pub fn run_command(
+    todo_list: &mut TodoList,
+    config: &Config,
+    args: &[String],
+) -> Result<bool, String> {
+    let state = new_state(todo_list, config);
+    for path in plugin_paths(config)? {
+        let mut plugin = load(&path, &state)?;
+        let info = describe_plugin(&mut plugin, &path)?;
+        if !info.commands.iter().any(|x| x.name == args[0]) {
+            continue;
+        }
+        let input = CommandInput {
+            command: &args[0],
+            args: &args[1..],
+        };
+        let out = call(&mut plugin, &path, "command", to_json(&input)?)?;
+        if !out.is_empty() {
+            println!("{}", out.trim_end());
+        }
+        let state = state.get().map_err(|err| err.to_string())?;
+        *todo_list = state.lock().unwrap().list.clone();
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+pub fn render(todo_list: &TodoList, config: &Config, renderer: &str) -> Result<String, String> {
+    let state = new_state(todo_list, config);
+    for path in plugin_paths(config)? {
+        let mut plugin = load(&path, &state)?;
+        let info = describe_plugin(&mut plugin, &path)?;
+        if !info.renderers.iter().any(|x| x == renderer) {
+            continue;
+        }
+        let input = RenderInput {
+            renderer,
+            items: &todo_list.items,
+        };
+        return call(&mut plugin, &path, "render", to_json(&input)?);
+    }
+    Err(tr!("no_renderer", format = format!("{:?}", renderer)))
+}
+
+fn to_json(input: &impl serde::Serialize) -> Result<String, String> {
+    serde_json::to_string(input).map_err(|err| tr!("encode_json_failed", error = err))
+}
@@ -0,0 +1,169 @@
+use crate::i18n::tr;
+use crate::todo::*;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
+
+// the events of one save often come in bursts, e.g. truncate and write
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+// notices when another process writes the data file. the dir is watched since
+// editors and sync tools often replace the file instead of writing to it.
+pub struct FileWatcher {
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    file_name: OsString,
+}
+
+impl FileWatcher {
+    pub fn new(file_path: &Path) -> Result<Self, String> {
+        let dir = file_path.parent().unwrap_or(Path::new("."));
+        let file_name = file_path
+            .file_name()
+            .ok_or(tr!("invalid_file", file = format!("{:?}", file_path)))?
+            .to_owned();
+        let (sender, events) = channel();
+        let mut watcher = notify::recommended_watcher(sender).map_err(|err| {
+            tr!(
+                "watch_file_failed",
+                file = format!("{:?}", file_path),
+                error = err
+            )
+        })?;
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|err| {
+                tr!(
+                    "watch_file_failed",
+                    file = format!("{:?}", file_path),
+                    error = err
+                )
+            })?;
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            file_name,
+        })
+    }
+
+    // wait until the file changed, returns false when the timeout passed first
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<bool, String> {
+        loop {
+            let event = match timeout {
+                Some(timeout) => match self.events.recv_timeout(timeout) {
+                    Ok(event) => event,
+                    Err(RecvTimeoutError::Timeout) => return Ok(false),
+                    Err(RecvTimeoutError::Disconnected) => break,
+                },
+                None => match self.events.recv() {
+                    Ok(event) => event,
+                    Err(_) => break,
+                },
+            };
+            let event = event.map_err(|err| tr!("watch_failed", error = err))?;
+            if self.is_change(&event) {
+                while self.events.recv_timeout(DEBOUNCE).is_ok() {}
+                return Ok(true);
+            }
+        }
+        Err(tr!("watcher_stopped"))
+    }
+
+    fn is_change(&self, event: &Event) -> bool {
+        let of_file = event
+            .paths
+            .iter()
+            .any(|path| path.file_name() == Some(self.file_name.as_os_str()));
+        of_file && !matches!(event.kind, EventKind::Access(_))
+    }
+}
+
+pub fn modified_time(file_path: &Path) -> Option<SystemTime> {
+    fs::metadata(file_path).and_then(|x| x.modified()).ok()
+}
+
+// merge by task id the changes of two lists which both started from base. a
+// task changed on one side takes that change, when both changed it ours wins.
+// a task deleted on one side is deleted unless the other side changed it. the
+// order is the one of theirs, tasks added in ours follow the task they follow
+// in ours.
+pub fn merge(base: &TodoList, ours: &TodoList, theirs: &TodoList) -> TodoList {
+    let find = |list: &TodoList, id: TaskId| list.items.iter().find(|x| x.id == id).cloned();
+    let mut items: Vec<Task> = vec![];
+    for task in theirs.items.iter() {
+        let base_task = find(base, task.id);
+        match find(ours, task.id) {
+            Some(our) if base_task.as_ref() != Some(&our) => items.push(our),
+            Some(_) => items.push(task.clone()),
+            // deleted in ours
+            None if base_task.as_ref() == Some(task) => {}
+            None => items.push(task.clone()),
+        }
+    }
+    for (idx, task) in ours.items.iter().enumerate() {
+        let in_theirs = find(theirs, task.id).is_some();
+        let unchanged = find(base, task.id).as_ref() == Some(task);
+        if in_theirs || unchanged {
+            continue;
+        }
+        let pos = ours.items[..idx]
+            .iter()
+            .rev()
+            .find_map(|prev| items.iter().position(|x| x.id == prev.id))
+            .map_or(0, |x| x + 1);
+        items.insert(pos, task.clone());
+    }
+    TodoList { items }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(tasks: &[(u64, &str)]) -> TodoList {
+        let items = tasks
+            .iter()
+            .map(|(id, title)| Task {
+                id: TaskId(*id),
+                ..Task::new(title)
+            })
+            .collect();
+        TodoList { items }
+    }
+
+    #[test]
+    fn merge_lists() {
+        let base = list(&[(1, "a"), (2, "b"), (3, "c"), (4, "d")]);
+        // ours edits a, deletes c and adds x after b
+        let ours = list(&[(1, "a1"), (2, "b"), (5, "x"), (4, "d")]);
+        // theirs edits b, deletes d, moves a to the end and adds y
+        let theirs = list(&[(2, "b2"), (3, "c"), (6, "y"), (1, "a")]);
+        let merged = merge(&base, &ours, &theirs);
+        let titles: Vec<_> = merged.items.iter().map(|x| x.title.as_str()).collect();
+        assert_eq!(titles, vec!["b2", "x", "y", "a1"]);
+
+        // a task changed on one side is kept even if the other side deleted it
+        let ours = list(&[(1, "a"), (2, "b"), (3, "c"), (4, "d1")]);
+        let merged = merge(&base, &ours, &theirs);
+        let titles: Vec<_> = merged.items.iter().map(|x| x.title.as_str()).collect();
+        assert_eq!(titles, vec!["b2", "c", "d1", "y", "a"]);
+    }
+
+    #[test]
+    fn watch_file() {
+        let dir = std::env::temp_dir().join(format!("todo0-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file_path = dir.join("todo.json");
+        fs::write(&file_path, "{}").unwrap();
+        let watcher = FileWatcher::new(&file_path).unwrap();
+        fs::write(dir.join("other.json"), "{}").unwrap();
+        assert!(!watcher.wait(Some(Duration::from_millis(300))).unwrap());
+        fs::write(&file_path, "{\"items\": []}").unwrap();
+        assert!(watcher.wait(Some(Duration::from_secs(5))).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
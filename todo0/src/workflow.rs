@@ -0,0 +1,206 @@
+use crate::i18n::tr;
+use crate::term::{pad, truncate};
+use crate::todo::*;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+// the columns a task moves through, the first state is where new tasks start
+// and the last one means done. e.g.
+//   "workflow": {
+//     "states": ["todo", "doing", "review", "done"],
+//     "transitions": { "todo": ["doing"], "doing": ["review"], "review": ["doing", "done"] }
+//   }
+// without transitions for a state, the task can move from it to any state.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Workflow {
+    pub states: Vec<String>,
+    pub transitions: HashMap<String, Vec<String>>,
+}
+
+const BOARD_COLUMN_WIDTH: usize = 24;
+
+impl Default for Workflow {
+    fn default() -> Self {
+        Self {
+            states: ["todo", "doing", "review", "done"]
+                .iter()
+                .map(|x| x.to_string())
+                .collect(),
+            transitions: HashMap::new(),
+        }
+    }
+}
+
+impl Workflow {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.states.len() < 2 {
+            return Err(tr!("too_few_states"));
+        }
+        for (from, targets) in self.transitions.iter() {
+            for state in std::iter::once(from).chain(targets.iter()) {
+                if !self.states.contains(state) {
+                    return Err(tr!(
+                        "unknown_transition_state",
+                        state = format!("{:?}", state)
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn initial(&self) -> &str {
+        &self.states[0]
+    }
+
+    pub fn final_state(&self) -> &str {
+        &self.states[self.states.len() - 1]
+    }
+
+    // the state of the task, tasks with an unknown state are in the first column
+    pub fn state_of<'a>(&'a self, task: &'a Task) -> &'a str {
+        if task.done {
+            self.final_state()
+        } else if self.states.contains(&task.state) {
+            &task.state
+        } else {
+            self.initial()
+        }
+    }
+
+    pub fn can_move(&self, from: &str, to: &str) -> bool {
+        match self.transitions.get(from) {
+            None => true,
+            Some(targets) => targets.iter().any(|x| x == to),
+        }
+    }
+
+    // move the task to the state following the allowed transitions
+    pub fn move_to(&self, task: &Task, to: &str) -> Result<Task, String> {
+        if !self.states.iter().any(|x| x == to) {
+            return Err(tr!(
+                "unknown_state",
+                state = format!("{:?}", to),
+                states = self.states.join(", ")
+            ));
+        }
+        let from = self.state_of(task);
+        if from != to && !self.can_move(from, to) {
+            return Err(tr!(
+                "invalid_transition",
+                title = task.title,
+                from = from,
+                to = to
+            ));
+        }
+        Ok(self.set_state(task, to))
+    }
+
+    // set the state without checking the transitions, used by check and uncheck
+    pub fn set_state(&self, task: &Task, to: &str) -> Task {
+        let done = to == self.final_state();
+        let done_at = match (done, task.done) {
+            (true, true) => task.done_at,
+            (true, false) => Some(Local::now()),
+            (false, _) => None,
+        };
+        Task {
+            state: to.to_string(),
+            done,
+            done_at,
+            ..task.clone()
+        }
+    }
+
+    // render the tasks in one column per state
+    pub fn format_board(&self, list: &TodoList) -> String {
+        let width = BOARD_COLUMN_WIDTH;
+        let columns: Vec<Vec<String>> = self
+            .states
+            .iter()
+            .map(|state| {
+                list.items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, task)| self.state_of(task) == state)
+                    .map(|(idx, task)| truncate(&format!("{}. {}", idx + 1, task.title), width))
+                    .collect()
+            })
+            .collect();
+
+        let mut buf = String::new();
+        let mut header = vec![];
+        for (state, column) in self.states.iter().zip(columns.iter()) {
+            let title = format!("{} ({})", state.to_uppercase(), column.len());
+            header.push(pad(&truncate(&title, width), width));
+        }
+        writeln!(buf, "{}", header.join("  ").trim_end()).unwrap();
+        let rule = vec!["-".repeat(width); self.states.len()];
+        write!(buf, "{}", rule.join("  ")).unwrap();
+
+        let rows = columns.iter().map(|x| x.len()).max().unwrap_or(0);
+        for row in 0..rows {
+            let line: Vec<_> = columns
+                .iter()
+                .map(|column| pad(column.get(row).map(|x| x.as_str()).unwrap_or(""), width))
+                .collect();
+            write!(buf, "\n{}", line.join("  ").trim_end()).unwrap();
+        }
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_between_states() {
+        let mut workflow = Workflow::default();
+        workflow
+            .transitions
+            .insert(String::from("todo"), vec![String::from("doing")]);
+        workflow.validate().unwrap();
+
+        let task = Task::new("A");
+        assert_eq!(workflow.state_of(&task), "todo");
+        assert!(workflow.move_to(&task, "review").is_err());
+        assert!(workflow.move_to(&task, "blocked").is_err());
+
+        let task = workflow.move_to(&task, "doing").unwrap();
+        assert_eq!(workflow.state_of(&task), "doing");
+        let task = workflow.move_to(&task, "done").unwrap();
+        assert!(task.done);
+
+        // check and uncheck ignore the transitions
+        let task = workflow.set_state(&task, workflow.initial());
+        assert!(!task.done);
+        assert_eq!(workflow.state_of(&task), "todo");
+    }
+
+    #[test]
+    fn format_board() {
+        let workflow = Workflow {
+            states: vec![String::from("todo"), String::from("done")],
+            transitions: HashMap::new(),
+        };
+        let mut list = TodoList::new();
+        list.add(None, Task::new("A")).unwrap();
+        list.add(None, Task::new("a very long title which does not fit"))
+            .unwrap();
+        list.items[0].done = true;
+        assert_eq!(
+            workflow.format_board(&list),
+            [
+                "TODO (1)                  DONE (1)",
+                "------------------------  ------------------------",
+                "2. a very long title wh…  1. A",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,248 @@
+// golden output tests, each command of the session is run against a fresh
+// home dir and the outputs are compared with tests/golden/session.txt.
+// run with UPDATE_GOLDEN=1 to write the file after an intended change.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const SESSION: &[&str] = &[
+    "list",
+    "add write docs",
+    "add fix login #work",
+    "add 1 plan release v1.2 #work",
+    "list",
+    "edit 2 write the docs",
+    "check 3",
+    "uncheck 3",
+    "check 3",
+    "assign 1 alice",
+    "list --mine",
+    "list --bogus",
+    "edit",
+    "unassign 1",
+    "unassign 9",
+    "assign 2 alice",
+    "prio 1 high",
+    "mv-state 2 review",
+    "board",
+    "block 1 on 2",
+    "ready",
+    "graph",
+    "unblock 1",
+    "unblock 1",
+    "ready",
+    "next",
+    "list --sort urgency",
+    "stats --since 2024-03-01 --until 2024-03-03",
+    "start 2",
+    "stop",
+    "report --since 2024-03-01",
+    "template save release 1-2 --var version=1.2",
+    "template list",
+    "template apply release --var version=1.3",
+    "template del release",
+    "export --format ics",
+    "import {home}/todo.ics",
+    "export --format org",
+    "import {home}/todo.txt",
+    "scan {home}/src",
+    "del 4 5",
+    "list",
+    "batch -c add ship it; check 4; uncheck 3",
+    "batch -c add broken; check 9",
+    "list",
+    "edit ship it now",
+    "snapshots",
+    "diff 3",
+    "restore 3",
+    "diff 2",
+    "list",
+    "plugins",
+    "reset",
+    "list",
+    "help",
+];
+
+fn golden_path() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/session.txt")
+}
+
+fn run(home: &Path, args: &[String]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_todo0"))
+        .args(args)
+        .env("HOME", home)
+        .env("NO_COLOR", "1")
+        .env("TODO0_USER", "alice")
+        .env_remove("TODO0_FILE")
+        .env_remove("COLUMNS")
+        .env_remove("TODO0_PASSPHRASE")
+        .env("LANG", "C")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    match output.status.code() {
+        Some(0) => stdout,
+        code => format!("{}[exit {:?}]\n", stdout, code),
+    }
+}
+
+// replace what changes between runs: task ids, timestamps and the names of
+// snapshots, which are their times
+fn redact(output: &str) -> String {
+    let mut lines = vec![];
+    for line in output.lines() {
+        let (name, _) = line.split_once(':').unwrap_or(("", ""));
+        if ["DTSTAMP", "CREATED", "COMPLETED", "CLOSED"].contains(&name) {
+            lines.push(format!("{}:<time>", name));
+            continue;
+        }
+        let line = redact_snapshot_names(line);
+        let mut out = String::new();
+        let mut digits = String::new();
+        for c in line.chars().chain(std::iter::once('\n')) {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                continue;
+            }
+            match digits.len() {
+                0 => {}
+                // ids are random u64s, shorter ones are very unlikely
+                12.. => out.push_str("<id>"),
+                _ => out.push_str(&digits),
+            }
+            digits.clear();
+            if c != '\n' {
+                out.push(c);
+            }
+        }
+        lines.push(out);
+    }
+    lines.join("\n")
+}
+
+// "20240310-120005.123" -> "<snapshot>"
+fn redact_snapshot_names(line: &str) -> String {
+    let is_name = |x: &[u8]| {
+        x.len() == 19
+            && x.iter().enumerate().all(|(i, c)| match i {
+                8 => *c == b'-',
+                15 => *c == b'.',
+                _ => c.is_ascii_digit(),
+            })
+    };
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) =
+        (0..rest.len()).find(|i| rest.get(*i..*i + 19).is_some_and(|x| is_name(x.as_bytes())))
+    {
+        out.push_str(&rest[..start]);
+        out.push_str("<snapshot>");
+        rest = &rest[start + 19..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[test]
+fn session_output() {
+    let home = std::env::temp_dir().join(format!("todo0-cli-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(home.join("src")).unwrap();
+    fs::write(
+        home.join("src/main.rs"),
+        "fn main() {} // TODO: error handling\n",
+    )
+    .unwrap();
+    fs::write(home.join("todo.txt"), "write docs\n").unwrap();
+
+    let mut transcript = String::new();
+    for line in SESSION {
+        let line = line.replace("{home}", home.to_str().unwrap());
+        // the script after "-c" is one argument
+        let (head, script) = match line.split_once(" -c ") {
+            Some((head, script)) => (head, Some(format!("-c\n{}", script))),
+            None => (line.as_str(), None),
+        };
+        let args: Vec<String> = head
+            .split_whitespace()
+            .chain(script.iter().flat_map(|x| x.splitn(2, '\n')))
+            .map(String::from)
+            .collect();
+        let output = run(&home, &args);
+        if line.starts_with("export --format ics") {
+            fs::write(home.join("todo.ics"), &output).unwrap();
+        }
+        let line = line.replace(home.to_str().unwrap(), "$HOME");
+        let output = output.replace(home.to_str().unwrap(), "$HOME");
+        transcript.push_str(&format!("$ todo0 {}\n{}\n", line, redact(&output)));
+    }
+    fs::remove_dir_all(&home).unwrap();
+
+    let path = golden_path();
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &transcript).unwrap();
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_default();
+    assert!(
+        transcript == expected,
+        "output differs from {:?}, run with UPDATE_GOLDEN=1 to update it\n{}",
+        path,
+        transcript
+    );
+}
+
+// a file todo0 can not load, e.g. of a newer version or not json, is left as
+// it is instead of being overwritten with an empty list
+#[test]
+fn unreadable_file_is_kept() {
+    let home = std::env::temp_dir().join(format!("todo0-cli-keep-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    let file_path = home.join(".rustexp/todo0/todo.json");
+    fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+    for content in [r#"{"version": 999, "items": []}"#, "{not json"] {
+        fs::write(&file_path, content).unwrap();
+        let output = run(&home, &["add".to_string(), "x".to_string()]);
+        assert!(output.ends_with("[exit Some(1)]\n"), "{}", output);
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), content);
+    }
+    fs::remove_dir_all(&home).unwrap();
+}
+
+// a post hook only runs once the change is saved, here a pre hook of the same
+// command cancels it, so the post hook of the first task must not run
+#[test]
+fn post_hooks_wait_for_save() {
+    let home = std::env::temp_dir().join(format!("todo0-cli-hooks-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&home);
+    let dir = home.join(".rustexp/todo0");
+    fs::create_dir_all(&dir).unwrap();
+    let config = r#"{ "hooks": [
+        { "stage": "pre", "event": "check", "command": "! grep -q '\"title\":\"b\"'" },
+        { "stage": "post", "event": "check", "command": "cat >> \"$HOME/post.log\"" }
+    ] }"#;
+    fs::write(dir.join("config.json"), config).unwrap();
+    let args = |line: &str| line.split(' ').map(String::from).collect::<Vec<_>>();
+    run(&home, &args("add a"));
+    run(&home, &args("add b"));
+    let output = run(&home, &args("check 1 2"));
+    assert!(output.ends_with("[exit Some(1)]\n"), "{}", output);
+    assert!(!home.join("post.log").exists());
+
+    run(&home, &args("check 1"));
+    let log = fs::read_to_string(home.join("post.log")).unwrap();
+    assert!(log.contains("\"title\":\"a\""), "{}", log);
+
+    // a config which can not be read is reported, not a panic
+    fs::write(
+        dir.join("config.json"),
+        r#"{ "hooks": [{ "stage": "x" }] }"#,
+    )
+    .unwrap();
+    let output = run(&home, &args("list"));
+    assert_eq!(output, "[exit Some(1)]\n");
+    fs::remove_dir_all(&home).unwrap();
+}